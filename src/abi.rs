@@ -0,0 +1,55 @@
+//! Poor man's Solidity ABI encoding.
+
+use crate::address::Address;
+use k256::elliptic_curve::{bigint::Encoding as _, sec1::ToEncodedPoint as _};
+
+pub fn address(a: Address) -> [u8; 32] {
+    let mut b = [0_u8; 32];
+    b[12..].copy_from_slice(a.as_slice());
+    b
+}
+
+pub fn scalar(a: &k256::Scalar) -> [u8; 32] {
+    k256::U256::from(a).to_be_bytes()
+}
+
+pub fn coord(a: &k256::ProjectivePoint) -> [u8; 64] {
+    a.to_encoded_point(false).as_bytes()[1..]
+        .try_into()
+        .unwrap()
+}
+
+pub fn uint(a: u64) -> [u8; 32] {
+    let mut b = [0_u8; 32];
+    b[24..].copy_from_slice(&a.to_be_bytes());
+    b
+}
+
+/// Returns the 32-byte word at the specified index.
+pub fn decode_word(data: &[u8], index: usize) -> Option<[u8; 32]> {
+    data.get(index * 32..)?.get(..32)?.try_into().ok()
+}
+
+pub fn decode_address(data: &[u8], index: usize) -> Option<Address> {
+    let word = decode_word(data, index)?;
+    if word[..12].iter().any(|&b| b != 0) {
+        return None;
+    }
+    Some(Address::from_bytes(word[12..].try_into().unwrap()))
+}
+
+pub fn decode_uint(data: &[u8], index: usize) -> Option<u64> {
+    let word = decode_word(data, index)?;
+    if word[..24].iter().any(|&b| b != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(word[24..].try_into().unwrap()))
+}
+
+/// Decodes dynamic `bytes`, whose offset is the word at the specified index.
+pub fn decode_bytes(data: &[u8], index: usize) -> Option<&[u8]> {
+    let offset = usize::try_from(decode_uint(data, index)?).ok()?;
+    let tail = data.get(offset..)?;
+    let len = usize::try_from(decode_uint(tail, 0)?).ok()?;
+    tail.get(32..)?.get(..len)
+}
@@ -1,11 +1,13 @@
-use crate::{fmt::Hex, keccak};
+use crate::{fmt::Hex, hex, keccak};
+use frost::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use k256::elliptic_curve::sec1::ToEncodedPoint as _;
 use std::{
     fmt::{self, Display, Formatter},
-    str,
+    str::{self, FromStr},
 };
 
 /// Ethereum public address.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
 pub struct Address([u8; 20]);
 
 impl Address {
@@ -16,6 +18,11 @@ impl Address {
         Self(bytes)
     }
 
+    /// Creates an address from its bytes.
+    pub fn from_bytes(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
     /// Returns the address as a slice of bytes.
     pub fn as_slice(&self) -> &[u8] {
         &self.0
@@ -40,3 +47,31 @@ impl Display for Address {
         f.write_str(str::from_utf8(&checksummed).unwrap())
     }
 }
+
+impl FromStr for Address {
+    type Err = hex::DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s).map(Self)
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|e| de::Error::custom(format!("invalid address: {e}")))
+    }
+}
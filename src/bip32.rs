@@ -0,0 +1,151 @@
+//! BIP-32 hierarchical deterministic derivation of secp256k1 secret keys from
+//! BIP-39 seeds, as used by Ethereum wallets.
+
+use hmac::{Hmac, Mac as _};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField as _, sec1::ToEncodedPoint as _},
+};
+use sha2::Sha512;
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+use zeroize::Zeroizing;
+
+/// A BIP-32 derivation path, such as `m/44'/60'/0'/0/0`.
+#[derive(Clone, Debug)]
+pub struct Path(Vec<u32>);
+
+impl Path {
+    /// The offset of hardened indices.
+    const HARDENED: u32 = 1 << 31;
+
+    /// Returns the indices of the path, with hardened indices offset by
+    /// `2^31`.
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Returns whether the path has any hardened indices.
+    pub fn is_hardened(&self) -> bool {
+        self.0.iter().any(|index| index & Self::HARDENED != 0)
+    }
+}
+
+impl Default for Path {
+    /// The derivation path of the first Ethereum account.
+    fn default() -> Self {
+        let h = Self::HARDENED;
+        Self(vec![44 + h, 60 + h, h, 0, 0])
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            if index & Self::HARDENED != 0 {
+                write!(f, "/{}'", index & !Self::HARDENED)?;
+            } else {
+                write!(f, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Path {
+    type Err = InvalidPath;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split('/');
+        if components.next() != Some("m") {
+            return Err(InvalidPath);
+        }
+        components
+            .map(|component| {
+                let (index, offset) = match component
+                    .strip_suffix('\'')
+                    .or_else(|| component.strip_suffix('h'))
+                {
+                    Some(index) => (index, Self::HARDENED),
+                    None => (component, 0),
+                };
+                match index.parse::<u32>() {
+                    Ok(index) if index < Self::HARDENED => Ok(index + offset),
+                    _ => Err(InvalidPath),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Derives the secret key at a path from a seed.
+pub fn derive(seed: &[u8], path: &Path) -> Result<Zeroizing<[u8; 32]>, InvalidKey> {
+    let (mut key, mut chain_code) = split(hmac(b"Bitcoin seed", &[seed]));
+    let mut scalar = to_scalar(&key)?;
+    for index in &path.0 {
+        let data = if index & Path::HARDENED != 0 {
+            Zeroizing::new([&[0][..], &key[..], &index.to_be_bytes()].concat())
+        } else {
+            let point = (ProjectivePoint::GENERATOR * scalar).to_encoded_point(true);
+            Zeroizing::new([point.as_bytes(), &index.to_be_bytes()].concat())
+        };
+        let (tweak, child_chain_code) = split(hmac(&chain_code[..], &[&data]));
+        scalar += to_scalar(&tweak)?;
+        if scalar.is_zero().into() {
+            return Err(InvalidKey);
+        }
+        *key = scalar.to_bytes().into();
+        chain_code = child_chain_code;
+    }
+    Ok(key)
+}
+
+fn hmac(key: &[u8], data: &[&[u8]]) -> Zeroizing<[u8; 64]> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key length");
+    for data in data {
+        mac.update(data);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().into())
+}
+
+fn split(bytes: Zeroizing<[u8; 64]>) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    (
+        Zeroizing::new(bytes[..32].try_into().unwrap()),
+        Zeroizing::new(bytes[32..].try_into().unwrap()),
+    )
+}
+
+fn to_scalar(bytes: &[u8; 32]) -> Result<Scalar, InvalidKey> {
+    Option::<Scalar>::from(Scalar::from_repr((*bytes).into()))
+        .filter(|scalar| !bool::from(scalar.is_zero()))
+        .ok_or(InvalidKey)
+}
+
+/// An error parsing a derivation path.
+#[derive(Debug)]
+pub struct InvalidPath;
+
+impl Display for InvalidPath {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("invalid derivation path, expected `m/44'/60'/0'/0/0`")
+    }
+}
+
+impl std::error::Error for InvalidPath {}
+
+/// The derived key is invalid, which happens with a negligible probability
+/// and requires using a different path.
+#[derive(Debug)]
+pub struct InvalidKey;
+
+impl Display for InvalidKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("derived key is invalid, use a different derivation path")
+    }
+}
+
+impl std::error::Error for InvalidKey {}
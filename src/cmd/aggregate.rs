@@ -1,40 +1,127 @@
 use crate::{
-    cmd::{self, Root},
-    data::round2::SignatureSharePackage,
+    address::Address,
+    cmd::{self, Input, Output, Role, Root, audit, json},
+    data::{round1::SigningPackage, round2::SignatureSharePackage},
+    derive, evm,
+    fmt::{Hex, Identifier},
+    rerandomize::RandomizedParams,
 };
 use argh::FromArgs;
-use std::fs;
+use std::{collections::BTreeMap, fs};
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "aggregate")]
 /// aggregate round-2 signature shares
-pub struct Command {}
+pub struct Command {
+    /// signature shares to aggregate, or `-` for standard input; can be
+    /// specified multiple times and defaults to all signature shares in the
+    /// root directory
+    #[argh(option, long = "in")]
+    inputs: Vec<Input>,
+
+    /// the signing package, or `-` for standard input; defaults to the signing
+    /// package in the root directory
+    #[argh(option)]
+    signing_package: Option<Input>,
+
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// the non-hardened derivation path of the child key, such as `m/0/1`;
+    /// defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// where to write the signature, or `-` for standard output; defaults to
+    /// the root directory
+    #[argh(option, long = "out")]
+    output: Option<Output>,
+}
 
 impl Command {
     pub fn run(self, root: Root) -> cmd::Result {
-        let data = fs::read(root.public_key())?;
-        let pubkey = frost::keys::PublicKeyPackage::deserialize(&data)?;
+        let root = root.workspace(Role::Coordinator)?;
+        let input = self
+            .public_key_package
+            .unwrap_or_else(|| root.public_key().into());
+        let mut pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+        if let Some(path) = &self.path {
+            pubkey = derive::public_key_package(&pubkey, path);
+        }
+
+        let input = self
+            .signing_package
+            .unwrap_or_else(|| root.signing_package().into());
+        let package = SigningPackage::deserialize(&input.read()?)?;
+        let signing = package.signing();
 
-        let data = fs::read(root.signing_package())?;
-        let signing = frost::SigningPackage::deserialize(&data)?;
+        // The signature of a re-randomized signing session is for the
+        // randomized key, which also needs to be supported on-chain.
+        let params = package.randomizer().map(|randomizer| {
+            RandomizedParams::from_randomizer(pubkey.verifying_key(), randomizer)
+        });
+        let key = match &params {
+            Some(params) => *evm::verified_key(params.randomized_verifying_key())?,
+            None => *pubkey.verifying_key(),
+        };
 
-        let shares = root
-            .all_signature_shares()?
-            .map(|path| -> anyhow::Result<_> {
-                let data = fs::read(&path)?;
-                let share = SignatureSharePackage::deserialize(&data)?;
-                Ok((*share.identifier(), *share.signature()))
-            })
-            .collect::<Result<_, _>>()?;
+        // Only signature shares from the root directory are cleaned up,
+        // explicitly specified inputs are left untouched.
+        let from_root = self.inputs.is_empty();
+        let inputs = if from_root {
+            root.all_signature_shares()?.map(Input::from).collect()
+        } else {
+            self.inputs
+        };
+
+        let mut shares = BTreeMap::new();
+        for input in &inputs {
+            for share in SignatureSharePackage::deserialize_all(&input.read()?)? {
+                if shares
+                    .insert(*share.identifier(), *share.signature())
+                    .is_some()
+                {
+                    anyhow::bail!(
+                        "duplicate signature shares for participant {}",
+                        Identifier(share.identifier()),
+                    );
+                }
+            }
+        }
 
-        let signature = frost::aggregate(&signing, &shares, &pubkey)?;
+        let signature = match &params {
+            Some(params) => frost_rerandomized::aggregate(signing, &shares, &pubkey, params)?,
+            None => frost::aggregate(signing, &shares, &pubkey)?,
+        };
+        let data = signature.serialize()?;
 
-        fs::write(root.signature(), signature.serialize()?)?;
+        self.output
+            .unwrap_or_else(|| root.signature().into())
+            .write(&data)?;
+        audit::record(
+            &root,
+            &audit::Event::Aggregate {
+                address: Address::from_key(&key),
+                message: format!("{:#}", Hex(signing.message())),
+                participants: signing
+                    .signing_commitments()
+                    .keys()
+                    .copied()
+                    .map(json::Identifier)
+                    .collect(),
+                signing_package: json::fingerprint(&signing.serialize()?),
+                signature: json::fingerprint(&data),
+            },
+        )?;
 
         // Clean up the signature shares after aggregating them, as they are no
         // longer needed.
-        for path in root.all_signature_shares()? {
-            fs::remove_file(path)?;
+        if from_root {
+            for path in root.all_signature_shares()? {
+                fs::remove_file(path)?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,272 @@
+//! Tamper-evident audit log of signing activity.
+//!
+//! Each workspace keeps an `audit.log` file of JSON lines, one per event. Every
+//! entry records the Keccak-256 hash of the line before it, so modifying,
+//! removing or reordering an entry breaks the chain for all entries after it.
+//! The hash of the last entry, the head of the log, can be recorded elsewhere
+//! to also detect truncation. Entries only ever hold public information, such
+//! as message digests, participant identifiers and fingerprints of files.
+
+use crate::{
+    address::Address,
+    cmd::{self, Layout, Role, Root, json},
+    fmt::Hex,
+    hex, keccak,
+};
+use argh::FromArgs;
+use frost::serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Read as _, Write as _},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "audit")]
+/// inspect the audit log of signing activity
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    Verify(Verify),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "verify")]
+/// verify the hash chain of audit logs
+struct Verify {
+    /// the audit logs to verify; can be specified multiple times and defaults
+    /// to the audit logs of all workspaces in the root directory
+    #[argh(option, long = "in")]
+    inputs: Vec<PathBuf>,
+
+    /// the hash of an entry that the audit log must contain, such as a
+    /// previously recorded head, to detect truncation of the log; requires a
+    /// single audit log
+    #[argh(option, from_str_fn(parse_hash))]
+    head: Option<[u8; 32]>,
+}
+
+impl Command {
+    pub fn run(self, root: Root) -> cmd::Result {
+        match self.subcommand {
+            Subcommand::Verify(cmd) => cmd.run(root),
+        }
+    }
+}
+
+impl Verify {
+    fn run(self, root: Root) -> cmd::Result {
+        let logs = if self.inputs.is_empty() {
+            workspaces(&root)?
+                .into_iter()
+                .map(|workspace| workspace.audit_log())
+                .filter(|path| path.exists())
+                .collect()
+        } else {
+            self.inputs
+        };
+        if logs.is_empty() {
+            anyhow::bail!("no audit logs in the root directory");
+        }
+        if self.head.is_some() && logs.len() > 1 {
+            anyhow::bail!("--head requires a single audit log");
+        }
+
+        for path in &logs {
+            let hashes = verify(&fs::read_to_string(path)?)
+                .map_err(|e| anyhow::anyhow!("audit log {} is invalid: {e}", path.display()))?;
+            if let Some(head) = &self.head
+                && !hashes.contains(head)
+            {
+                anyhow::bail!(
+                    "audit log {} has no entry {:#}, it was truncated or rewritten",
+                    path.display(),
+                    Hex(head),
+                );
+            }
+            println!(
+                "{}: {} entries, head {:#}",
+                path.display(),
+                hashes.len(),
+                Hex(hashes.last().unwrap_or(&GENESIS)),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The previous hash of the first entry of an audit log.
+const GENESIS: [u8; 32] = [0; 32];
+
+/// An event recorded in the audit log.
+#[derive(Serialize)]
+#[serde(
+    crate = "::frost::serde",
+    tag = "event",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Event {
+    /// A key was split into key shares.
+    Split {
+        address: Address,
+        threshold: u16,
+        signers: u16,
+        public_key_package: String,
+    },
+    /// A participant generated nonces and commitments.
+    Commit {
+        participant: json::Identifier,
+        commitments: String,
+    },
+    /// The coordinator prepared a signing package.
+    Prepare {
+        message: String,
+        participants: Vec<json::Identifier>,
+        commitments: Vec<String>,
+        signing_package: String,
+    },
+    /// A participant produced a signature share.
+    Sign {
+        participant: json::Identifier,
+        message: String,
+        commitments: String,
+        signing_package: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+    /// The coordinator aggregated a signature.
+    Aggregate {
+        address: Address,
+        message: String,
+        participants: Vec<json::Identifier>,
+        signing_package: String,
+        signature: String,
+    },
+    /// A signature was verified.
+    Verify {
+        address: Address,
+        message: String,
+        signature: String,
+        valid: bool,
+    },
+    /// A participant's signature share was verified.
+    VerifyShare {
+        participant: json::Identifier,
+        message: String,
+        valid: bool,
+    },
+}
+
+/// An entry of the audit log.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde")]
+struct Entry<'a> {
+    previous: String,
+    timestamp: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// The fields of an entry needed to verify the chain.
+#[derive(Deserialize)]
+#[serde(crate = "::frost::serde")]
+struct Link {
+    previous: String,
+}
+
+/// Appends an event to the audit log of a workspace.
+///
+/// Nothing is recorded for workspaces that do not exist, such as when a
+/// `verify` with explicit inputs is run outside of any root directory, so that
+/// commands never create a root directory just for their audit log.
+pub fn record(root: &Root, event: &Event) -> io::Result<()> {
+    if !root.dir.is_dir() {
+        return Ok(());
+    }
+    let mut file = File::options()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(root.audit_log())?;
+    file.lock()?;
+    let mut log = String::new();
+    file.read_to_string(&mut log)?;
+    let previous = log
+        .lines()
+        .last()
+        .map_or(GENESIS, |line| keccak::v256(line.as_bytes()));
+
+    let entry = Entry {
+        previous: format!("{:#}", Hex(&previous)),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs()),
+        event,
+    };
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+    file.sync_all()
+}
+
+/// Verifies the hash chain of an audit log, returning the hashes of all of
+/// its entries.
+fn verify(log: &str) -> anyhow::Result<Vec<[u8; 32]>> {
+    let mut hashes = Vec::<[u8; 32]>::new();
+    for (number, line) in log.lines().enumerate() {
+        let link = serde_json::from_str::<Link>(line)
+            .map_err(|e| anyhow::anyhow!("line {}: {e}", number + 1))?;
+        let previous = hashes.last().unwrap_or(&GENESIS);
+        if hex::decode::<[u8; 32]>(&link.previous).ok().as_ref() != Some(previous) {
+            anyhow::bail!(
+                "line {} does not follow the previous entry, the log was modified",
+                number + 1,
+            );
+        }
+        hashes.push(keccak::v256(line.as_bytes()));
+    }
+    Ok(hashes)
+}
+
+/// Returns all workspaces of the root directory.
+fn workspaces(root: &Root) -> io::Result<Vec<Root>> {
+    if root.layout == Layout::Flat {
+        return Ok(vec![root.workspace_unchecked(Role::Coordinator)]);
+    }
+    let mut workspaces = vec![
+        root.workspace_unchecked(Role::Dealer),
+        root.workspace_unchecked(Role::Coordinator),
+    ];
+    let base = root.base();
+    let mut signers = Vec::new();
+    if base.exists() {
+        for entry in base.read_dir()? {
+            if let Some(index) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("signer-"))
+                .and_then(|index| index.parse().ok())
+            {
+                signers.push(index);
+            }
+        }
+    }
+    signers.sort_unstable();
+    workspaces.extend(
+        signers
+            .into_iter()
+            .map(|index| root.workspace_unchecked(Role::Signer(index))),
+    );
+    Ok(workspaces)
+}
+
+fn parse_hash(value: &str) -> Result<[u8; 32], String> {
+    hex::decode(value).map_err(|e| format!("invalid hash: {e}"))
+}
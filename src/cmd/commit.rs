@@ -1,9 +1,9 @@
 use crate::{
-    cmd::{self, Root},
+    cmd::{self, Input, Output, Role, Root, audit, json},
     data::round1::CommitmentsPackage,
 };
 use argh::FromArgs;
-use std::fs;
+use zeroize::Zeroizing;
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "commit")]
@@ -12,19 +12,46 @@ pub struct Command {
     /// share index
     #[argh(option, short = 'i')]
     share_index: usize,
+
+    /// the key share to read, or `-` for standard input; defaults to the key
+    /// share in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// where to write the commitments, or `-` for standard output; defaults to
+    /// the root directory
+    #[argh(option, long = "out")]
+    output: Option<Output>,
 }
 
 impl Command {
     pub fn run(self, root: Root) -> cmd::Result {
+        let root = root.workspace(Role::Signer(self.share_index))?;
         let mut rng = rand::thread_rng();
-        let data = fs::read(root.signing_key(self.share_index))?;
-        let key = frost::keys::KeyPackage::deserialize(&data)?;
+        let input = self
+            .input
+            .unwrap_or_else(|| root.signing_key(self.share_index).into());
+        let key = Zeroizing::new(frost::keys::KeyPackage::deserialize(&input.read_secret()?)?);
 
         let (nonces, commitments) = frost::round1::commit(key.signing_share(), &mut rng);
+        let nonces = Zeroizing::new(nonces);
         let commitments = CommitmentsPackage::new(*key.identifier(), commitments);
 
-        fs::write(root.nonces(self.share_index), nonces.serialize()?)?;
-        fs::write(root.commitments(self.share_index), commitments.serialize()?)?;
+        let _lock = root.lock()?;
+        cmd::write_secret(
+            &root.nonces(self.share_index),
+            &Zeroizing::new(nonces.serialize()?),
+        )?;
+        self.output
+            .unwrap_or_else(|| root.commitments(self.share_index).into())
+            .write(&commitments.serialize()?)?;
+        audit::record(
+            &root,
+            &audit::Event::Commit {
+                participant: json::Identifier(*key.identifier()),
+                commitments: json::fingerprint(&commitments.commitments().serialize()?),
+            },
+        )?;
 
         Ok(())
     }
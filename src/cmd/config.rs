@@ -0,0 +1,223 @@
+use crate::{
+    address::Address,
+    cmd::{self, Format, Layout, Root, groups, json},
+    tx::{self, Uint},
+};
+use argh::FromArgs;
+use frost::serde::{Deserialize, Serialize};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The file name of configuration files.
+const FILE_NAME: &str = "safe-frost.toml";
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "config")]
+/// inspect the `safe-frost.toml` configuration
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    Show(Show),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "show")]
+/// display the effective configuration, with command line options applied
+struct Show {}
+
+impl Command {
+    pub fn run(self, root: Root, config: &Config) -> cmd::Result {
+        match self.subcommand {
+            Subcommand::Show(_) => {
+                // Show the values that are actually in effect, including the
+                // built-in defaults of the global options.
+                let effective = Config {
+                    root_directory: Some(root.dir.clone()),
+                    layout: Some(root.layout),
+                    format: Some(config.format()),
+                    group: root.group.clone(),
+                    policy: config.policy.clone(),
+                    sources: Vec::new(),
+                    ..*config
+                };
+                if config.format() == Format::Json {
+                    json::print(&effective)?;
+                    return Ok(());
+                }
+                for source in &config.sources {
+                    println!("# loaded from {}", source.display());
+                }
+                print!("{}", toml::to_string(&effective)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Default options from `safe-frost.toml` configuration files.
+///
+/// The configuration is read from the user's configuration directory (usually
+/// `~/.config/safe-frost/`) and from the root directory, with the latter
+/// taking precedence. Options specified on the command line always take
+/// precedence over the configuration.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(
+    crate = "::frost::serde",
+    rename_all = "kebab-case",
+    deny_unknown_fields
+)]
+pub struct Config {
+    /// The FROST root directory, which can only be set in the user
+    /// configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_directory: Option<PathBuf>,
+    /// The layout of the root directory.
+    #[serde(default, with = "arg", skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    /// The named group to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// The chain ID of transactions that do not specify one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<Uint>,
+    /// The Safe of Safe transactions that do not specify one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe: Option<Address>,
+    /// The entry point of user operations that do not specify one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<Address>,
+    /// The output format of commands that display information.
+    #[serde(default, with = "arg", skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    /// The signing policy that `sign` evaluates transactions against, relative
+    /// to the configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PathBuf>,
+
+    /// The configuration files that were read.
+    #[serde(skip)]
+    sources: Vec<PathBuf>,
+}
+
+impl Config {
+    /// Loads the configuration for a root directory, specified on the command
+    /// line or otherwise by the user configuration.
+    pub fn load(root: Option<&Root>) -> anyhow::Result<Self> {
+        let mut config = match user_config() {
+            Some(path) => Self::read(&path)?.unwrap_or_default(),
+            None => Self::default(),
+        };
+
+        let dir = match (root, &config.root_directory) {
+            (Some(root), _) => root.dir.clone(),
+            (None, Some(dir)) => dir.clone(),
+            (None, None) => Root::default().dir,
+        };
+        let path = dir.join(FILE_NAME);
+        if let Some(local) = Self::read(&path)? {
+            if local.root_directory.is_some() {
+                anyhow::bail!(
+                    "{}: root-directory can only be set in the user configuration",
+                    path.display(),
+                );
+            }
+            config = Self {
+                root_directory: config.root_directory,
+                layout: local.layout.or(config.layout),
+                group: local.group.or(config.group),
+                chain_id: local.chain_id.or(config.chain_id),
+                safe: local.safe.or(config.safe),
+                entry_point: local.entry_point.or(config.entry_point),
+                format: local.format.or(config.format),
+                policy: local.policy.or(config.policy),
+                sources: [config.sources, local.sources].concat(),
+            };
+        }
+        Ok(config)
+    }
+
+    /// Reads a configuration file, if it exists.
+    fn read(path: &Path) -> anyhow::Result<Option<Self>> {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut config = toml::from_str::<Self>(&data)
+            .map_err(|e| anyhow::anyhow!("invalid configuration {}: {e}", path.display()))?;
+        if let Some(group) = &config.group {
+            groups::parse_name(group).map_err(anyhow::Error::msg)?;
+        }
+        // The policy is usually kept next to the configuration, so relative
+        // paths are relative to the configuration file.
+        if let Some(policy) = &mut config.policy
+            && let Some(dir) = path.parent()
+        {
+            *policy = dir.join(&*policy);
+        }
+        config.sources.push(path.to_owned());
+        Ok(Some(config))
+    }
+
+    /// Returns the output format of commands that display information.
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or_default()
+    }
+
+    /// Returns the defaults for fields that are omitted from JSON
+    /// transactions.
+    pub fn transaction_defaults(&self) -> tx::Defaults {
+        tx::Defaults {
+            chain_id: self.chain_id,
+            safe: self.safe,
+            entry_point: self.entry_point,
+        }
+    }
+}
+
+/// Returns the path of the user configuration file, following the XDG base
+/// directory specification.
+fn user_config() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(dir.join("safe-frost").join(FILE_NAME))
+}
+
+/// Serialization of optional values that are parsed like command line option
+/// values.
+mod arg {
+    use argh::FromArgValue;
+    use frost::serde::{Deserialize as _, Deserializer, Serializer, de};
+    use std::fmt::Display;
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromArgValue,
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        T::from_arg_value(&value)
+            .map(Some)
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    cmd::{self, Output, Role, Root},
+    paper,
+};
+use argh::FromArgs;
+use std::io::{self, IsTerminal as _};
+use zeroize::Zeroizing;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "export")]
+/// export key material for backups
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    Share(Share),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "share")]
+/// export a key share
+struct Share {
+    /// share index
+    #[argh(option, short = 'i')]
+    share_index: usize,
+
+    /// render the key share as a printable paper backup, with grouped
+    /// hexadecimal lines that each have a checksum
+    #[argh(switch)]
+    paper: bool,
+
+    /// where to write the key share, or `-` for standard output; defaults to
+    /// standard output, unless it is a terminal
+    #[argh(option, long = "out")]
+    output: Option<Output>,
+}
+
+impl Command {
+    pub fn run(self, root: Root) -> cmd::Result {
+        match self.subcommand {
+            Subcommand::Share(cmd) => cmd.run(root),
+        }
+    }
+}
+
+impl Share {
+    fn run(self, root: Root) -> cmd::Result {
+        let root = root.workspace(Role::Signer(self.share_index))?;
+        let data = cmd::read_secret(&root.signing_key(self.share_index))?;
+        let output = match self.output {
+            Some(output) => output,
+            None if io::stdout().is_terminal() => {
+                anyhow::bail!("refusing to write a key share to a terminal, use --out")
+            }
+            None => Output::Stdout,
+        };
+        if !self.paper {
+            return Ok(output.write_secret(&data)?);
+        }
+
+        let key = Zeroizing::new(frost::keys::KeyPackage::deserialize(&data)?);
+        if key.identifier() != &cmd::identifier(self.share_index)? {
+            anyhow::bail!("key share is not for share {}", self.share_index);
+        }
+        let share = paper::Share {
+            index: self.share_index.try_into()?,
+            threshold: *key.min_signers(),
+            signing_share: *key.signing_share(),
+            verifying_key: *key.verifying_key(),
+        };
+        output.write_secret(share.encode()?.as_bytes())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    address::Address,
+    cmd::{self, Artifact, Role, Root},
+    fmt::Coord,
+};
+use argh::FromArgs;
+use std::{collections::BTreeMap, fmt::Write as _, fs, io, path::Path};
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "groups")]
+/// manage named FROST groups in the root directory
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    List(List),
+    Show(Show),
+    Remove(Remove),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+/// list all named groups with their address, threshold and signer count
+struct List {}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "show")]
+/// display information of a named group
+struct Show {
+    /// the group to show, defaults to the selected `--group`
+    #[argh(positional, from_str_fn(parse_name))]
+    name: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "remove")]
+/// remove a named group and all of its files
+struct Remove {
+    /// the group to remove
+    #[argh(positional, from_str_fn(parse_name))]
+    name: String,
+
+    /// remove the group even if it holds key shares or nonces
+    #[argh(switch, short = 'f')]
+    force: bool,
+}
+
+impl Command {
+    pub fn run(self, root: Root) -> cmd::Result {
+        match self.subcommand {
+            Subcommand::List(_) => {
+                let index = Index::load(&root)?;
+                let width = index.0.keys().map(String::len).max().unwrap_or_default();
+                for (name, entry) in &index.0 {
+                    println!(
+                        "{name:width$}  {}  {} of {}",
+                        entry.address, entry.threshold, entry.signers,
+                    );
+                }
+            }
+            Subcommand::Show(cmd) => {
+                let name = cmd
+                    .name
+                    .or_else(|| root.group.clone())
+                    .ok_or_else(|| anyhow::anyhow!("no group specified"))?;
+                let index = Index::load(&root)?;
+                let entry = index
+                    .0
+                    .get(&name)
+                    .ok_or_else(|| anyhow::anyhow!("unknown group '{name}'"))?;
+
+                let group = root.clone().with_group(Some(name.clone()));
+                let path = [Role::Coordinator, Role::Dealer]
+                    .into_iter()
+                    .map(|role| group.workspace_unchecked(role).public_key())
+                    .find(|path| path.exists())
+                    .ok_or_else(|| anyhow::anyhow!("group '{name}' has no public key"))?;
+                let key = frost::keys::PublicKeyPackage::deserialize(&fs::read(path)?)?;
+
+                println!("address:    {}", entry.address);
+                println!("public key: {}", Coord(&key.verifying_key().to_element()));
+                println!("threshold:  {}", entry.threshold);
+                println!("signers:    {}", entry.signers);
+                println!("directory:  {}", root.group_dir(&name).display());
+            }
+            Subcommand::Remove(cmd) => {
+                let mut index = Index::load(&root)?;
+                let dir = root.group_dir(&cmd.name);
+                if index.0.remove(&cmd.name).is_none() && !dir.exists() {
+                    anyhow::bail!("unknown group '{}'", cmd.name);
+                }
+                if dir.exists() {
+                    if !cmd.force && holds_secrets(&dir)? {
+                        anyhow::bail!(
+                            "group '{}' holds key shares or nonces, use --force to remove it anyway",
+                            cmd.name,
+                        );
+                    }
+                    fs::remove_dir_all(dir)?;
+                }
+                index.save(&root)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records a newly split group in the index of the root directory.
+pub(super) fn record(
+    root: &Root,
+    name: &str,
+    address: Address,
+    threshold: u16,
+    signers: u16,
+) -> io::Result<()> {
+    let mut index = Index::load(root)?;
+    index.0.insert(
+        name.to_owned(),
+        Entry {
+            address: address.to_string(),
+            threshold,
+            signers,
+        },
+    );
+    index.save(root)
+}
+
+/// Returns the signer threshold recorded for a group in the index of the root
+/// directory, if any.
+pub(super) fn threshold(root: &Root, name: &str) -> io::Result<Option<u16>> {
+    let index = Index::load(root)?;
+    Ok(index.0.get(name).map(|entry| entry.threshold))
+}
+
+/// Parses a group name, which must be a non-empty string of ASCII letters,
+/// digits, `-` and `_`, other than the reserved `index` name.
+pub fn parse_name(value: &str) -> Result<String, String> {
+    if value.is_empty()
+        || value == "index"
+        || !value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return Err(format!("invalid group name '{value}'"));
+    }
+    Ok(value.to_owned())
+}
+
+/// The index of named groups, stored as tab-separated lines of group name,
+/// address, threshold and signer count.
+struct Index(BTreeMap<String, Entry>);
+
+struct Entry {
+    address: String,
+    threshold: u16,
+    signers: u16,
+}
+
+impl Index {
+    fn load(root: &Root) -> io::Result<Self> {
+        let data = match fs::read_to_string(root.groups_index()) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        let mut entries = BTreeMap::new();
+        for line in data.lines().filter(|line| !line.is_empty()) {
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid group index");
+            let mut fields = line.split('\t');
+            let mut field = || fields.next().ok_or_else(invalid);
+            let name = field()?.to_owned();
+            let address = field()?.to_owned();
+            let threshold = field()?.parse().map_err(|_| invalid())?;
+            let signers = field()?.parse().map_err(|_| invalid())?;
+            entries.insert(
+                name,
+                Entry {
+                    address,
+                    threshold,
+                    signers,
+                },
+            );
+        }
+        Ok(Self(entries))
+    }
+
+    fn save(&self, root: &Root) -> io::Result<()> {
+        let mut data = String::new();
+        for (name, entry) in &self.0 {
+            writeln!(
+                data,
+                "{name}\t{}\t{}\t{}",
+                entry.address, entry.threshold, entry.signers,
+            )
+            .unwrap();
+        }
+        fs::create_dir_all(root.groups_dir())?;
+        cmd::write(&root.groups_index(), data.as_bytes())
+    }
+}
+
+/// Returns whether or not a directory, or any of its subdirectories, holds
+/// key shares or nonces.
+fn holds_secrets(dir: &Path) -> io::Result<bool> {
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if holds_secrets(&path)? {
+                return Ok(true);
+            }
+        } else if let Some(Artifact::SigningKey(_) | Artifact::Nonces(_)) = path
+            .file_name()
+            .and_then(|name| Artifact::from_file_name(name.to_str()?))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,108 @@
+use crate::{
+    cmd::{self, Input, Role, Root},
+    paper,
+};
+use argh::FromArgs;
+use std::str;
+use zeroize::Zeroizing;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "import")]
+/// import key material from backups
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    Share(Share),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "share")]
+/// import a key share from a paper backup or an exported key share
+struct Share {
+    /// the paper backup or key share, or `-` for standard input
+    #[argh(option, long = "in")]
+    input: Input,
+
+    /// the expected share index, importing is refused if the backup is for a
+    /// different share; required for exported key shares, which do not record
+    /// their share index
+    #[argh(option, short = 'i')]
+    share_index: Option<usize>,
+
+    /// overwrite an existing key share
+    #[argh(switch, short = 'f')]
+    force: bool,
+}
+
+impl Command {
+    pub fn run(self, root: Root) -> cmd::Result {
+        match self.subcommand {
+            Subcommand::Share(cmd) => cmd.run(root),
+        }
+    }
+}
+
+impl Share {
+    fn run(self, root: Root) -> cmd::Result {
+        let data = self.input.read_secret()?;
+        let (index, key) = match str::from_utf8(&data) {
+            Ok(text) if text.trim_start().starts_with(char::is_alphabetic) => {
+                let share = paper::Share::decode(text)?;
+                let index = usize::from(share.index);
+                let key = frost::keys::KeyPackage::new(
+                    cmd::identifier(index)?,
+                    share.signing_share,
+                    share.signing_share.into(),
+                    share.verifying_key,
+                    share.threshold,
+                );
+                (index, Zeroizing::new(key))
+            }
+            _ => {
+                let index = self.share_index.ok_or_else(|| {
+                    anyhow::anyhow!("importing a key share requires --share-index")
+                })?;
+                let key = Zeroizing::new(frost::keys::KeyPackage::deserialize(&data)?);
+                if key.identifier() != &cmd::identifier(index)? {
+                    anyhow::bail!("key share is not for share {index}");
+                }
+                (index, key)
+            }
+        };
+        if let Some(expected) = self.share_index
+            && expected != index
+        {
+            anyhow::bail!("key share is for share {index}, expected share {expected}");
+        }
+
+        // Check the key share against the public key package of the signer,
+        // if there is one, so that an incorrect backup is never silently used.
+        let root = root.workspace(Role::Signer(index))?;
+        let public_key = root.public_key();
+        if public_key.exists() {
+            let pubkey = frost::keys::PublicKeyPackage::deserialize(&std::fs::read(public_key)?)?;
+            if pubkey.verifying_shares().get(key.identifier()) != Some(key.verifying_share())
+                || pubkey.verifying_key() != key.verifying_key()
+            {
+                anyhow::bail!("key share does not match the public key package");
+            }
+        }
+
+        root.ensure()?;
+        let path = root.signing_key(index);
+        let data = Zeroizing::new(key.serialize()?);
+        if self.force {
+            cmd::write_secret(&path, &data)?;
+        } else {
+            cmd::write_secret_new(&path, &data)?;
+        }
+        eprintln!("imported key share {index} to {}", path.display());
+
+        Ok(())
+    }
+}
@@ -1,11 +1,22 @@
 use crate::{
+    abi,
     address::Address,
-    cmd::{self, Root},
-    evm,
-    fmt::{Coord, Hex, Scalar},
+    cmd::{self, Format, Input, Role, Root, config::Config, groups, json, session::Session},
+    data, derive, evm,
+    fmt::{Coord, Hex, Identifier, Scalar},
+    hex,
+    rerandomize::{self, RandomizedParams, Randomizer},
+    schnorr,
+    tx::{Bytes, OwnerSignature, SafeTransaction, Transaction, Uint},
 };
 use argh::FromArgs;
-use std::fs;
+use frost::serde::{Deserialize, Serialize};
+use k256::ProjectivePoint;
+use std::{
+    fmt::{self, Display, Formatter},
+    fs, str,
+};
+use zeroize::Zeroizing;
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "info")]
@@ -18,6 +29,11 @@ pub struct Command {
     /// `ffi` cheatcodes
     #[argh(switch, short = 'e')]
     abi_encode: bool,
+
+    /// output in JSON format, intended for use with other tooling; the
+    /// default with the `json` output format
+    #[argh(switch, short = 'j')]
+    json: bool,
 }
 
 #[derive(FromArgs)]
@@ -25,12 +41,35 @@ pub struct Command {
 enum Subcommand {
     PublicKey(PublicKey),
     Signature(Signature),
+    SigningPackage(SigningPackage),
+    ExecTransaction(ExecTransaction),
+    KeyShare(KeyShare),
+    Group(Group),
 }
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "public-key")]
 /// display information of a FROST public key
-struct PublicKey {}
+struct PublicKey {
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// the non-hardened derivation path of the child key, such as `m/0/1`;
+    /// defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// display the randomized key of a re-randomized signing package
+    #[argh(switch)]
+    rerandomized: bool,
+
+    /// the re-randomized signing package, or `-` for standard input; defaults
+    /// to the signing package in the root directory
+    #[argh(option)]
+    signing_package: Option<Input>,
+}
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "signature")]
@@ -40,37 +79,211 @@ struct Signature {
     /// EIP-7702 `FROSTAccount` implementation for signature verification
     #[argh(switch, short = 'p')]
     with_public_key: bool,
+
+    /// the signature, or `-` for standard input; defaults to the signature in
+    /// the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// the non-hardened derivation path of the child key included in the
+    /// signature, such as `m/0/1`; defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// include the randomized key of a re-randomized signing package in the
+    /// signature
+    #[argh(switch)]
+    rerandomized: bool,
+
+    /// the re-randomized signing package, or `-` for standard input; defaults
+    /// to the signing package in the root directory
+    #[argh(option)]
+    signing_package: Option<Input>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "signing-package")]
+/// display information of a FROST signing package
+struct SigningPackage {
+    /// the signing package, or `-` for standard input; defaults to the signing
+    /// package in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// the JSON Safe transaction or user operation being signed, or `-` for
+    /// standard input; defaults to the transaction attached to the signing
+    /// package in the root directory, if any
+    #[argh(option, short = 't')]
+    transaction: Option<Input>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "exec-transaction")]
+/// decode a Safe `execTransaction` call and verify its FROST signatures
+struct ExecTransaction {
+    /// the hexadecimal `execTransaction` calldata, or a JSON transaction with
+    /// `to`, `input` (or `data`) and `chainId` fields, or `-` for standard
+    /// input
+    #[argh(option, long = "in")]
+    input: Input,
+
+    /// the Safe executing the transaction; defaults to the `to` address of a
+    /// JSON transaction, or the Safe of the configuration
+    #[argh(option)]
+    safe: Option<Address>,
+
+    /// the chain ID; defaults to the chain ID of a JSON transaction, or the
+    /// chain ID of the configuration
+    #[argh(option)]
+    chain_id: Option<Uint>,
+
+    /// the Safe nonce the transaction was executed with
+    #[argh(option)]
+    nonce: Uint,
+
+    /// the signatures end with a `SafeFROSTCoSigner` co-signature
+    #[argh(switch)]
+    co_signed: bool,
+
+    /// the group public key to verify FROST signatures with, either as `{x,y}`
+    /// coordinates, SEC1 encoded, or the ABI encoding of `info --abi-encode
+    /// public-key`
+    #[argh(option, from_str_fn(parse_public_key))]
+    public_key: Option<schnorr::Point>,
+
+    /// the `SafeFROSTSigner` owner of the group public key, whose contract
+    /// signatures are decoded as FROST signatures; contract signatures of the
+    /// group key's address are always decoded as FROST signatures
+    #[argh(option)]
+    signer: Option<Address>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "key-share")]
+/// display public information of a participant's key share
+struct KeyShare {
+    /// the participant's share index
+    #[argh(option, short = 'i')]
+    share_index: usize,
+
+    /// the key share, or `-` for standard input; defaults to the participant's
+    /// key share in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "group")]
+/// display information of the group, its verifying shares and which key
+/// shares, nonces and commitments exist in the root directory
+struct Group {
+    /// the share index of a key share in the root directory to read the signer
+    /// threshold from, for groups that have no recorded threshold
+    #[argh(option, short = 'i')]
+    share_index: Option<usize>,
 }
 
 impl Command {
-    pub fn run(self, root: Root) -> cmd::Result {
+    pub fn run(self, root: Root, config: &Config) -> cmd::Result {
+        if self.abi_encode && self.json {
+            anyhow::bail!("--abi-encode and --json cannot be combined");
+        }
+        let as_json = self.json || (!self.abi_encode && config.format() == Format::Json);
+        let threshold = match &root.group {
+            Some(group) => groups::threshold(&root, group)?,
+            None => None,
+        };
+        let base = root;
+        let root = base.workspace_unchecked(Role::Coordinator);
         match self.subcommand {
-            Subcommand::PublicKey(_) => {
-                let data = fs::read(root.public_key())?;
-                let key = frost::keys::PublicKeyPackage::deserialize(&data)?;
-                let key = evm::verified_public_key(&key)?;
+            Subcommand::PublicKey(cmd) => {
+                let input = cmd.input.unwrap_or_else(|| root.public_key().into());
+                let data = input.read()?;
+                let mut package = frost::keys::PublicKeyPackage::deserialize(&data)?;
+                if let Some(path) = &cmd.path {
+                    package = derive::public_key_package(&package, path);
+                }
+                let params = match (cmd.rerandomized, cmd.signing_package) {
+                    (true, input) => Some(RandomizedParams::from_randomizer(
+                        package.verifying_key(),
+                        randomizer(input.unwrap_or_else(|| root.signing_package().into()))?,
+                    )),
+                    (false, Some(_)) => anyhow::bail!("--signing-package requires --rerandomized"),
+                    (false, None) => None,
+                };
+                let key = evm::verified_key(match &params {
+                    Some(params) => params.randomized_verifying_key(),
+                    None => package.verifying_key(),
+                })?;
 
                 if self.abi_encode {
                     let mut buf = Vec::new();
                     buf.extend_from_slice(&abi::address(Address::from_key(key)));
                     buf.extend_from_slice(&abi::coord(&key.to_element()));
                     print!("{}", Hex(&buf))
+                } else if as_json {
+                    json::print(&PublicKeyInfo {
+                        key: json::PublicKey::new(key),
+                        path: cmd.path.as_ref().map(ToString::to_string),
+                        threshold,
+                        verifying_shares: package
+                            .verifying_shares()
+                            .iter()
+                            .map(|(identifier, share)| {
+                                let share = match &params {
+                                    Some(params) => rerandomize::verifying_share(share, params),
+                                    None => *share,
+                                };
+                                VerifyingShareInfo {
+                                    identifier: json::Identifier(*identifier),
+                                    point: json::Point::new(&share.to_element()),
+                                }
+                            })
+                            .collect(),
+                        fingerprint: json::fingerprint(&data),
+                    })?;
                 } else {
                     println!("address:    {}", Address::from_key(key));
                     println!("public key: {}", Coord(&key.to_element()));
                 }
             }
             Subcommand::Signature(cmd) => {
-                let data = fs::read(root.signature())?;
+                let input = cmd.input.unwrap_or_else(|| root.signature().into());
+                let data = input.read()?;
                 let signature = frost::Signature::deserialize(&data)?;
 
                 let key = if cmd.with_public_key {
-                    let data = fs::read(root.public_key())?;
-                    Some(frost::keys::PublicKeyPackage::deserialize(&data)?)
+                    let input = cmd
+                        .public_key_package
+                        .unwrap_or_else(|| root.public_key().into());
+                    let key = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+                    let key = match &cmd.path {
+                        Some(path) => derive::public_key_package(&key, path),
+                        None => key,
+                    };
+                    Some(match (cmd.rerandomized, cmd.signing_package) {
+                        (true, input) => rerandomize::randomized_key(
+                            key.verifying_key(),
+                            randomizer(input.unwrap_or_else(|| root.signing_package().into()))?,
+                        ),
+                        (false, Some(_)) => {
+                            anyhow::bail!("--signing-package requires --rerandomized")
+                        }
+                        (false, None) => *key.verifying_key(),
+                    })
+                } else if cmd.path.is_some() || cmd.rerandomized || cmd.signing_package.is_some() {
+                    anyhow::bail!(
+                        "--path, --rerandomized and --signing-package require --with-public-key"
+                    );
                 } else {
                     None
                 };
-                let key = key.as_ref().map(evm::verified_public_key).transpose()?;
+                let key = key.as_ref().map(evm::verified_key).transpose()?;
 
                 if self.abi_encode {
                     let mut buf = Vec::new();
@@ -80,6 +293,13 @@ impl Command {
                     buf.extend_from_slice(&abi::coord(signature.R()));
                     buf.extend_from_slice(&abi::scalar(signature.z()));
                     print!("{}", Hex(&buf))
+                } else if as_json {
+                    json::print(&SignatureInfo {
+                        public_key: key.map(json::PublicKey::new),
+                        r: json::Point::new(signature.R()),
+                        z: Scalar(signature.z()).to_string(),
+                        fingerprint: json::fingerprint(&data),
+                    })?;
                 } else if let Some(key) = &key {
                     println!("public key: {}", Coord(&key.to_element()));
                     println!("R:          {}", Coord(signature.R()));
@@ -89,29 +309,512 @@ impl Command {
                     println!("z: {}", Scalar(signature.z()));
                 }
             }
+            Subcommand::SigningPackage(cmd) => {
+                if self.abi_encode {
+                    anyhow::bail!("signing packages cannot be ABI encoded");
+                }
+
+                let session = Session::load(
+                    &root,
+                    cmd.input,
+                    cmd.transaction,
+                    &config.transaction_defaults(),
+                )?;
+                if as_json {
+                    let commitments = session
+                        .signing
+                        .signing_commitments()
+                        .iter()
+                        .map(|(identifier, commitments)| -> anyhow::Result<_> {
+                            Ok(CommitmentsInfo {
+                                identifier: json::Identifier(*identifier),
+                                hiding: format!("{:#}", Hex(&commitments.hiding().serialize()?)),
+                                binding: format!("{:#}", Hex(&commitments.binding().serialize()?)),
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    json::print(&SigningPackageInfo {
+                        message: format!("{:#}", Hex(session.signing.message())),
+                        participants: session
+                            .signing
+                            .signing_commitments()
+                            .keys()
+                            .copied()
+                            .map(json::Identifier)
+                            .collect(),
+                        commitments,
+                        rerandomized: session.randomizer.is_some(),
+                        transaction: session.transaction.as_ref(),
+                        fingerprint: json::fingerprint(&session.signing.serialize()?),
+                    })?;
+                    return Ok(());
+                }
+
+                println!("{session}");
+                println!("commitments:");
+                for (identifier, commitments) in session.signing.signing_commitments() {
+                    println!(
+                        "  {}: hiding {:#} binding {:#}",
+                        Identifier(identifier),
+                        Hex(&commitments.hiding().serialize()?),
+                        Hex(&commitments.binding().serialize()?),
+                    );
+                }
+            }
+            Subcommand::ExecTransaction(cmd) => {
+                if self.abi_encode {
+                    anyhow::bail!("Safe transactions cannot be ABI encoded");
+                }
+                cmd.run(config, as_json)?;
+            }
+            Subcommand::KeyShare(cmd) => {
+                if self.abi_encode {
+                    anyhow::bail!("key shares cannot be ABI encoded");
+                }
+                cmd.run(&base, as_json)?;
+            }
+            Subcommand::Group(cmd) => {
+                if self.abi_encode {
+                    anyhow::bail!("groups cannot be ABI encoded");
+                }
+                cmd.run(&base, threshold, as_json)?;
+            }
         }
         Ok(())
     }
 }
 
-/// Poor man's Solidity ABI encoding.
-mod abi {
-    use crate::address::Address;
-    use k256::elliptic_curve::{bigint::Encoding as _, sec1::ToEncodedPoint as _};
+/// Reads the randomizer of the re-randomized signing package in the root
+/// directory.
+fn randomizer(input: Input) -> anyhow::Result<Randomizer> {
+    data::round1::SigningPackage::deserialize(&input.read()?)?
+        .randomizer()
+        .ok_or_else(|| anyhow::anyhow!("the signing package is not re-randomized"))
+}
+
+impl ExecTransaction {
+    fn run(self, config: &Config, as_json: bool) -> cmd::Result {
+        let data = self.input.read()?;
+        let data = str::from_utf8(&data)?.trim();
+        let (calldata, to, chain_id) = if data.starts_with('{') {
+            let dump = serde_json::from_str::<Dump>(data)?;
+            let calldata = dump
+                .input
+                .or(dump.data)
+                .ok_or_else(|| anyhow::anyhow!("JSON transaction has no `input` calldata"))?;
+            (calldata.0, dump.to, dump.chain_id)
+        } else {
+            (hex::decode(data)?, None, None)
+        };
+        let safe = self
+            .safe
+            .or(to)
+            .or(config.safe)
+            .ok_or_else(|| anyhow::anyhow!("a --safe address is required"))?;
+        let chain_id = self
+            .chain_id
+            .or(chain_id)
+            .or(config.chain_id)
+            .ok_or_else(|| anyhow::anyhow!("a --chain-id is required"))?;
+
+        let (transaction, signatures) =
+            SafeTransaction::decode_exec_transaction(&calldata, chain_id, safe, self.nonce)
+                .ok_or_else(|| anyhow::anyhow!("invalid `execTransaction` calldata"))?;
+        let (owners, co_signature) = OwnerSignature::decode_all(signatures, self.co_signed)
+            .ok_or_else(|| anyhow::anyhow!("invalid `execTransaction` signatures"))?;
+        let hash = transaction.hash();
+        let key = self.public_key.map(schnorr::Point::to_key).transpose()?;
+
+        // FROST signatures are ABI encoded `R` coordinates and `z` scalar, and
+        // are only verified when the group public key is known.
+        let verify_frost = |signature: &[u8]| {
+            key.map_or(Verification::NotVerified, |key| {
+                let valid = schnorr::Signature::from_bytes(signature)
+                    .and_then(|signature| signature.to_frost())
+                    .is_ok_and(|signature| key.verify(&hash, &signature).is_ok());
+                if valid {
+                    Verification::Valid
+                } else {
+                    Verification::Invalid
+                }
+            })
+        };
+
+        // Contract signatures of other owners can be for any EIP-1271 verifier,
+        // so only decode the ones of the FROST signer as FROST signatures.
+        let frost_owners = [self.signer, key.as_ref().map(Address::from_key)];
+        let is_frost = |owner: &Address| frost_owners.contains(&Some(*owner));
+
+        let signatures = owners
+            .iter()
+            .map(|signature| {
+                let owner = signature.owner(&hash);
+                match signature {
+                    OwnerSignature::Contract {
+                        owner: contract,
+                        signature,
+                    } if signature.len() == 96 && is_frost(contract) => SignatureEntry::Frost {
+                        owner,
+                        verification: verify_frost(signature),
+                    },
+                    OwnerSignature::Contract { signature, .. } => SignatureEntry::Contract {
+                        owner,
+                        signature: Bytes(signature.clone()),
+                    },
+                    OwnerSignature::ApprovedHash { .. } => SignatureEntry::ApprovedHash { owner },
+                    OwnerSignature::Ecdsa { .. } => SignatureEntry::Ecdsa { owner },
+                }
+            })
+            .collect::<Vec<_>>();
+        let co_signature = co_signature.map(verify_frost);
+        let invalid = co_signature == Some(Verification::Invalid)
+            || signatures.iter().any(|signature| {
+                matches!(
+                    signature,
+                    SignatureEntry::Frost {
+                        verification: Verification::Invalid,
+                        ..
+                    }
+                )
+            });
 
-    pub fn address(a: Address) -> [u8; 32] {
-        let mut b = [0_u8; 32];
-        b[12..].copy_from_slice(a.as_slice());
-        b
+        if as_json {
+            json::print(&ExecTransactionInfo {
+                transaction: &transaction,
+                safe_tx_hash: format!("{:#}", Hex(&hash)),
+                signatures: &signatures,
+                co_signature,
+            })?;
+        } else {
+            println!("{transaction}");
+            println!("safe tx hash: {:#}", Hex(&hash));
+            println!("signatures:");
+            for signature in &signatures {
+                println!("  {signature}");
+            }
+            if let Some(co_signature) = co_signature {
+                println!("co-signature: FROST signature ({co_signature})");
+            }
+        }
+
+        if invalid {
+            anyhow::bail!("transaction has invalid FROST signatures");
+        }
+        Ok(())
     }
+}
+
+impl KeyShare {
+    fn run(self, root: &Root, as_json: bool) -> cmd::Result {
+        let data = match self.input {
+            Some(input) => input.read_secret()?,
+            None => {
+                let root = root.workspace(Role::Signer(self.share_index))?;
+                cmd::read_secret(&root.signing_key(self.share_index))?
+            }
+        };
+        let key = Zeroizing::new(frost::keys::KeyPackage::deserialize(&data)?);
+        if key.identifier() != &cmd::identifier(self.share_index)? {
+            anyhow::bail!("key share is not for share {}", self.share_index);
+        }
+
+        if as_json {
+            json::print(&KeyShareInfo {
+                share_index: self.share_index,
+                identifier: json::Identifier(*key.identifier()),
+                min_signers: *key.min_signers(),
+                verifying_share: json::Point::new(&key.verifying_share().to_element()),
+                public_key: json::PublicKey::new(key.verifying_key()),
+            })?;
+        } else {
+            println!("share index:     {}", self.share_index);
+            println!("identifier:      {}", Identifier(key.identifier()));
+            println!("min signers:     {}", key.min_signers());
+            println!(
+                "verifying share: {}",
+                Coord(&key.verifying_share().to_element()),
+            );
+            println!(
+                "address:         {}",
+                Address::from_key(key.verifying_key())
+            );
+            println!(
+                "public key:      {}",
+                Coord(&key.verifying_key().to_element()),
+            );
+        }
+        Ok(())
+    }
+}
 
-    pub fn scalar(a: &k256::Scalar) -> [u8; 32] {
-        k256::U256::from(a).to_be_bytes()
+impl Group {
+    fn run(self, root: &Root, threshold: Option<u16>, as_json: bool) -> cmd::Result {
+        let coordinator = root.workspace_unchecked(Role::Coordinator);
+        let path = [
+            coordinator.public_key(),
+            root.workspace_unchecked(Role::Dealer).public_key(),
+        ]
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| anyhow::anyhow!("root directory has no public key package"))?;
+        let data = fs::read(path)?;
+        let package = frost::keys::PublicKeyPackage::deserialize(&data)?;
+        let key = package.verifying_key();
+
+        // Key shares, nonces and commitments are kept in the participant's own
+        // workspace, although commitments may already have been passed on to
+        // the coordinator.
+        let mut shares = Vec::new();
+        for index in 0..package.verifying_shares().len() {
+            let identifier = cmd::identifier(index)?;
+            let share = package
+                .verifying_shares()
+                .get(&identifier)
+                .ok_or_else(|| anyhow::anyhow!("public key package has no share {index}"))?;
+            let signer = root.workspace_unchecked(Role::Signer(index));
+            shares.push(ShareInfo {
+                share_index: index,
+                identifier: json::Identifier(identifier),
+                verifying_share: json::Point::new(&share.to_element()),
+                element: share.to_element(),
+                key_share: signer.signing_key(index).exists(),
+                nonces: signer.nonces(index).exists(),
+                commitments: signer.commitments(index).exists()
+                    || coordinator.commitments(index).exists(),
+            });
+        }
+        // Key shares are secret, so they are only read for the threshold when
+        // explicitly requested.
+        let threshold = match (threshold, self.share_index) {
+            (Some(threshold), _) => Some(threshold),
+            (None, Some(index)) => {
+                let path = [Role::Signer(index), Role::Dealer]
+                    .into_iter()
+                    .map(|role| root.workspace_unchecked(role).signing_key(index))
+                    .find(|path| path.exists())
+                    .ok_or_else(|| anyhow::anyhow!("root directory has no key share {index}"))?;
+                let share = Zeroizing::new(frost::keys::KeyPackage::deserialize(
+                    &cmd::read_secret(&path)?,
+                )?);
+                if share.identifier() != &cmd::identifier(index)? || share.verifying_key() != key {
+                    anyhow::bail!("key share {index} is not for this group");
+                }
+                Some(*share.min_signers())
+            }
+            (None, None) => None,
+        };
+
+        if as_json {
+            json::print(&GroupInfo {
+                key: json::PublicKey::new(key),
+                threshold,
+                signers: shares.len(),
+                shares: &shares,
+                fingerprint: json::fingerprint(&data),
+            })?;
+            return Ok(());
+        }
+
+        println!("address:    {}", Address::from_key(key));
+        println!("public key: {}", Coord(&key.to_element()));
+        match threshold {
+            Some(threshold) => println!("threshold:  {threshold}"),
+            None => println!("threshold:  unknown"),
+        }
+        println!("signers:    {}", shares.len());
+        println!("shares:");
+        for share in &shares {
+            let files = [
+                (share.key_share, "key share"),
+                (share.nonces, "nonces"),
+                (share.commitments, "commitments"),
+            ]
+            .into_iter()
+            .filter_map(|(exists, file)| exists.then_some(file))
+            .collect::<Vec<_>>();
+            println!(
+                "  {}: identifier {} verifying share {}{}{}",
+                share.share_index,
+                Identifier(&share.identifier.0),
+                Coord(&share.element),
+                if files.is_empty() { "" } else { ", has " },
+                files.join(", "),
+            );
+        }
+        Ok(())
     }
+}
 
-    pub fn coord(a: &k256::ProjectivePoint) -> [u8; 64] {
-        a.to_encoded_point(false).as_bytes()[1..]
-            .try_into()
-            .unwrap()
+/// The result of verifying a FROST signature of a Safe transaction.
+#[derive(Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+enum Verification {
+    Valid,
+    Invalid,
+    NotVerified,
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Valid => "valid",
+            Self::Invalid => "INVALID",
+            Self::NotVerified => "not verified",
+        })
+    }
+}
+
+/// An owner signature of an `execTransaction` call.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", tag = "type", rename_all = "camelCase")]
+enum SignatureEntry {
+    Frost {
+        owner: Option<Address>,
+        verification: Verification,
+    },
+    Contract {
+        owner: Option<Address>,
+        signature: Bytes,
+    },
+    ApprovedHash {
+        owner: Option<Address>,
+    },
+    Ecdsa {
+        owner: Option<Address>,
+    },
+}
+
+impl Display for SignatureEntry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (Self::Frost { owner, .. }
+        | Self::Contract { owner, .. }
+        | Self::ApprovedHash { owner }
+        | Self::Ecdsa { owner }) = self;
+        match owner {
+            Some(owner) => write!(f, "{owner}: ")?,
+            None => f.write_str("unknown owner: ")?,
+        }
+        match self {
+            Self::Frost { verification, .. } => write!(f, "FROST signature ({verification})"),
+            Self::Contract { signature, .. } => write!(f, "contract signature {signature}"),
+            Self::ApprovedHash { .. } => f.write_str("approved hash"),
+            Self::Ecdsa { .. } => f.write_str("ECDSA signature"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct PublicKeyInfo {
+    #[serde(flatten)]
+    key: json::PublicKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold: Option<u16>,
+    verifying_shares: Vec<VerifyingShareInfo>,
+    fingerprint: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct VerifyingShareInfo {
+    identifier: json::Identifier,
+    #[serde(flatten)]
+    point: json::Point,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct SignatureInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<json::PublicKey>,
+    r: json::Point,
+    z: String,
+    fingerprint: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct SigningPackageInfo<'a> {
+    message: String,
+    participants: Vec<json::Identifier>,
+    commitments: Vec<CommitmentsInfo>,
+    rerandomized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction: Option<&'a Transaction>,
+    fingerprint: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct CommitmentsInfo {
+    identifier: json::Identifier,
+    hiding: String,
+    binding: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct KeyShareInfo {
+    share_index: usize,
+    identifier: json::Identifier,
+    min_signers: u16,
+    verifying_share: json::Point,
+    public_key: json::PublicKey,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct GroupInfo<'a> {
+    #[serde(flatten)]
+    key: json::PublicKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold: Option<u16>,
+    signers: usize,
+    shares: &'a [ShareInfo],
+    fingerprint: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct ShareInfo {
+    share_index: usize,
+    identifier: json::Identifier,
+    verifying_share: json::Point,
+    #[serde(skip)]
+    element: ProjectivePoint,
+    key_share: bool,
+    nonces: bool,
+    commitments: bool,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct ExecTransactionInfo<'a> {
+    transaction: &'a SafeTransaction,
+    safe_tx_hash: String,
+    signatures: &'a [SignatureEntry],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    co_signature: Option<Verification>,
+}
+
+/// A JSON transaction, such as returned by `eth_getTransactionByHash`.
+#[derive(Deserialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct Dump {
+    to: Option<Address>,
+    input: Option<Bytes>,
+    data: Option<Bytes>,
+    chain_id: Option<Uint>,
+}
+
+fn parse_public_key(value: &str) -> Result<schnorr::Point, String> {
+    match value.parse() {
+        Ok(schnorr::PublicKey::Point(point)) => Ok(point),
+        Ok(schnorr::PublicKey::Address(_)) => {
+            Err("invalid public key: an address cannot be used to verify signatures".to_owned())
+        }
+        Err(e) => Err(format!("invalid public key: {e}")),
     }
 }
@@ -0,0 +1,70 @@
+//! Machine-readable JSON output.
+
+use crate::{address::Address, fmt, fmt::Hex, keccak};
+use frost::serde::{Serialize, Serializer};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+/// Prints a value as JSON to standard output.
+pub fn print<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Returns the fingerprint of a file, its Keccak-256 hash.
+pub fn fingerprint(data: &[u8]) -> String {
+    format!("{:#}", Hex(&keccak::v256(data)))
+}
+
+/// A secp256k1 point, with its affine coordinates and its SEC1 compressed
+/// encoding.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde")]
+pub struct Point {
+    x: String,
+    y: String,
+    compressed: String,
+}
+
+impl Point {
+    pub fn new<P>(point: &P) -> Self
+    where
+        P: ToEncodedPoint<k256::Secp256k1>,
+    {
+        let uncompressed = point.to_encoded_point(false);
+        Self {
+            x: format!("{:#}", Hex(&uncompressed.as_bytes()[1..33])),
+            y: format!("{:#}", Hex(&uncompressed.as_bytes()[33..65])),
+            compressed: format!("{:#}", Hex(point.to_encoded_point(true).as_bytes())),
+        }
+    }
+}
+
+/// A public key, with its address.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde")]
+pub struct PublicKey {
+    address: Address,
+    #[serde(flatten)]
+    point: Point,
+}
+
+impl PublicKey {
+    pub fn new(key: &frost::VerifyingKey) -> Self {
+        Self {
+            address: Address::from_key(key),
+            point: Point::new(&key.to_element()),
+        }
+    }
+}
+
+/// A FROST identifier, serialized as a decimal string.
+pub struct Identifier(pub frost::Identifier);
+
+impl Serialize for Identifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&fmt::Identifier(&self.0))
+    }
+}
@@ -1,13 +1,31 @@
 pub mod aggregate;
+pub mod audit;
 pub mod commit;
+pub mod config;
+pub mod export;
+pub mod groups;
+pub mod import;
 pub mod info;
+mod json;
 pub mod prepare;
+pub mod reconstruct;
+mod session;
 pub mod sign;
 pub mod split;
+pub mod status;
 pub mod verify;
 
+use crate::{fmt::Hex, hex};
 use argh::{FromArgValue, FromArgs};
-use std::{fs, io, path::PathBuf};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::{self, File},
+    io::{self, Read as _, Write as _},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use zeroize::Zeroizing;
 
 pub type Result = std::result::Result<(), anyhow::Error>;
 
@@ -21,108 +39,646 @@ pub enum Subcommand {
     Sign(sign::Command),
     Aggregate(aggregate::Command),
     Verify(verify::Command),
+    Groups(groups::Command),
+    Reconstruct(reconstruct::Command),
+    Export(export::Command),
+    Import(import::Command),
+    Status(status::Command),
+    Audit(audit::Command),
+    Config(config::Command),
 }
 
 impl Subcommand {
-    pub fn run(self, root: Root) -> Result {
+    pub fn run(self, root: Root, config: &config::Config) -> Result {
         match self {
-            Self::Info(cmd) => cmd.run(root),
+            Self::Info(cmd) => cmd.run(root, config),
             Self::Split(cmd) => cmd.run(root),
             Self::Commit(cmd) => cmd.run(root),
-            Self::Prepare(cmd) => cmd.run(root),
-            Self::Sign(cmd) => cmd.run(root),
+            Self::Prepare(cmd) => cmd.run(root, config),
+            Self::Sign(cmd) => cmd.run(root, config),
             Self::Aggregate(cmd) => cmd.run(root),
             Self::Verify(cmd) => cmd.run(root),
+            Self::Groups(cmd) => cmd.run(root),
+            Self::Reconstruct(cmd) => cmd.run(root),
+            Self::Export(cmd) => cmd.run(root),
+            Self::Import(cmd) => cmd.run(root),
+            Self::Status(cmd) => cmd.run(root, config),
+            Self::Audit(cmd) => cmd.run(root),
+            Self::Config(cmd) => cmd.run(root, config),
         }
     }
 }
 
 /// The FROST root directory.
-pub struct Root(PathBuf);
+#[derive(Clone)]
+pub struct Root {
+    dir: PathBuf,
+    group: Option<String>,
+    layout: Layout,
+}
 
 impl Root {
+    /// Selects a named group in the root directory.
+    pub fn with_group(self, group: Option<String>) -> Self {
+        Self { group, ..self }
+    }
+
+    /// Sets the layout of the root directory.
+    pub fn with_layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Returns the workspace for the specified role, without checking its
+    /// contents.
+    ///
+    /// With the flat layout, all roles share the root directory itself.
+    fn workspace_unchecked(&self, role: Role) -> Self {
+        let base = self.base();
+        let dir = match (self.layout, role) {
+            (Layout::Flat, _) => base,
+            (Layout::Roles, Role::Dealer) => base.join("dealer"),
+            (Layout::Roles, Role::Signer(index)) => base.join(format!("signer-{index}")),
+            (Layout::Roles, Role::Coordinator) => base.join("coordinator"),
+        };
+        Self {
+            dir,
+            group: None,
+            layout: self.layout,
+        }
+    }
+
+    /// Returns the directory of the selected group, which holds the role
+    /// workspaces with the role layout.
+    fn base(&self) -> PathBuf {
+        match &self.group {
+            Some(group) => self.group_dir(group),
+            None => self.dir.clone(),
+        }
+    }
+
+    fn groups_dir(&self) -> PathBuf {
+        self.dir.join("groups")
+    }
+
+    fn group_dir(&self, group: &str) -> PathBuf {
+        self.groups_dir().join(group)
+    }
+
+    fn groups_index(&self) -> PathBuf {
+        self.groups_dir().join("index")
+    }
+
+    /// Returns the workspace for the specified role.
+    ///
+    /// With the role layout, this errors if the workspace holds material that
+    /// the role should not have access to, such as another signer's key share.
+    fn workspace(&self, role: Role) -> anyhow::Result<Self> {
+        let workspace = self.workspace_unchecked(role);
+        if self.layout == Layout::Flat || !workspace.dir.exists() {
+            return Ok(workspace);
+        }
+        for entry in workspace.dir.read_dir()? {
+            let path = entry?.path();
+            let Some(artifact) = path
+                .file_name()
+                .and_then(|name| Artifact::from_file_name(name.to_str()?))
+            else {
+                continue;
+            };
+            if !role.may_hold(artifact) {
+                anyhow::bail!(
+                    "{} workspace holds {} it should not have: {}",
+                    role,
+                    artifact,
+                    path.display(),
+                );
+            }
+        }
+        Ok(workspace)
+    }
+
     fn ensure(&self) -> io::Result<()> {
-        fs::create_dir_all(&self.0)
+        fs::create_dir_all(&self.dir)
     }
 
     fn public_key(&self) -> PathBuf {
-        self.0.join("key.pub")
+        self.dir.join("key.pub")
     }
 
     fn signing_key(&self, index: usize) -> PathBuf {
-        self.0.join(format!("key.{index}"))
+        self.dir.join(format!("key.{index}"))
+    }
+
+    fn all_signing_keys(&self) -> io::Result<impl Iterator<Item = PathBuf>> {
+        self.all(|artifact| matches!(artifact, Artifact::SigningKey(_)))
     }
 
     fn nonces(&self, index: usize) -> PathBuf {
-        self.0.join(format!("round1.{index}.nonces"))
+        self.dir.join(format!("round1.{index}.nonces"))
     }
 
     fn commitments(&self, index: usize) -> PathBuf {
-        self.0.join(format!("round1.{index}.commitments"))
+        self.dir.join(format!("round1.{index}.commitments"))
     }
 
     fn all_commitments(&self) -> io::Result<impl Iterator<Item = PathBuf>> {
-        let mut result = Vec::new();
-        for entry in self.0.read_dir()? {
-            let path = entry?.path();
-            if path
-                .file_name()
-                .and_then(|name| {
-                    name.to_str()?
-                        .strip_prefix("round1.")?
-                        .strip_suffix(".commitments")?
-                        .parse::<usize>()
-                        .ok()
-                })
-                .is_some()
-            {
-                result.push(path);
-            };
-        }
-        Ok(result.into_iter())
+        self.all(|artifact| matches!(artifact, Artifact::Commitments(_)))
     }
 
     fn signing_package(&self) -> PathBuf {
-        self.0.join("round1")
+        self.dir.join("round1")
+    }
+
+    fn transaction(&self) -> PathBuf {
+        self.dir.join("round1.tx")
     }
 
     fn signature_share(&self, index: usize) -> PathBuf {
-        self.0.join(format!("round2.{index}"))
+        self.dir.join(format!("round2.{index}"))
     }
 
     fn all_signature_shares(&self) -> io::Result<impl Iterator<Item = PathBuf>> {
+        self.all(|artifact| matches!(artifact, Artifact::SignatureShare(_)))
+    }
+
+    fn signature(&self) -> PathBuf {
+        self.dir.join("round2")
+    }
+
+    fn consumed_nonces(&self) -> PathBuf {
+        self.dir.join("nonces.consumed")
+    }
+
+    fn audit_log(&self) -> PathBuf {
+        self.dir.join("audit.log")
+    }
+
+    /// Acquires an exclusive advisory lock on the root directory, which is
+    /// released when the returned file is dropped.
+    fn lock(&self) -> io::Result<File> {
+        self.ensure()?;
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join("lock"))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    fn all(&self, filter: impl Fn(Artifact) -> bool) -> io::Result<impl Iterator<Item = PathBuf>> {
         let mut result = Vec::new();
-        for entry in self.0.read_dir()? {
+        for entry in self.dir.read_dir()? {
             let path = entry?.path();
             if path
                 .file_name()
-                .and_then(|name| {
-                    name.to_str()?
-                        .strip_prefix("round2.")?
-                        .parse::<usize>()
-                        .ok()
-                })
-                .is_some()
+                .and_then(|name| Artifact::from_file_name(name.to_str()?))
+                .is_some_and(&filter)
             {
                 result.push(path);
             };
         }
         Ok(result.into_iter())
     }
-
-    fn signature(&self) -> PathBuf {
-        self.0.join("round2")
-    }
 }
 
 impl Default for Root {
     fn default() -> Self {
-        Self(PathBuf::from(".frost"))
+        Self {
+            dir: PathBuf::from(".frost"),
+            group: None,
+            layout: Layout::Flat,
+        }
+    }
+}
+
+impl From<PathBuf> for Root {
+    fn from(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            group: None,
+            layout: Layout::Flat,
+        }
     }
 }
 
 impl FromArgValue for Root {
     fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
-        Ok(Self(PathBuf::from(value)))
+        Ok(Self {
+            dir: PathBuf::from(value),
+            group: None,
+            layout: Layout::Flat,
+        })
+    }
+}
+
+/// The layout of files in the FROST root directory.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum Layout {
+    /// All files for all roles are kept in the root directory.
+    #[default]
+    Flat,
+    /// Files are kept in separate `dealer/`, `signer-${index}/` and
+    /// `coordinator/` workspaces in the root directory.
+    Roles,
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Flat => f.write_str("flat"),
+            Self::Roles => f.write_str("roles"),
+        }
+    }
+}
+
+impl FromArgValue for Layout {
+    fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
+        match value {
+            "flat" => Ok(Self::Flat),
+            "roles" => Ok(Self::Roles),
+            _ => Err(format!(
+                "invalid layout '{value}', expected 'flat' or 'roles'"
+            )),
+        }
+    }
+}
+
+/// The output format of commands that display information.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum Format {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON, intended for use with other tooling.
+    Json,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Text => f.write_str("text"),
+            Self::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl FromArgValue for Format {
+    fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
+        match value {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "invalid format '{value}', expected 'text' or 'json'"
+            )),
+        }
+    }
+}
+
+/// A role in the FROST signing process.
+#[derive(Clone, Copy)]
+enum Role {
+    Dealer,
+    Signer(usize),
+    Coordinator,
+}
+
+impl Role {
+    /// Returns whether or not the role is allowed to hold an artifact in its
+    /// workspace.
+    fn may_hold(self, artifact: Artifact) -> bool {
+        match (self, artifact) {
+            (_, Artifact::PublicKey) => true,
+            (Self::Dealer, Artifact::SigningKey(_)) => true,
+            (Self::Dealer, _) => false,
+            (Self::Signer(index), Artifact::SigningKey(i) | Artifact::Nonces(i)) => index == i,
+            (Self::Signer(_), _) => true,
+            (Self::Coordinator, Artifact::SigningKey(_) | Artifact::Nonces(_)) => false,
+            (Self::Coordinator, _) => true,
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Dealer => f.write_str("dealer"),
+            Self::Signer(index) => write!(f, "signer {index}"),
+            Self::Coordinator => f.write_str("coordinator"),
+        }
+    }
+}
+
+/// A file in the FROST root directory.
+#[derive(Clone, Copy)]
+enum Artifact {
+    PublicKey,
+    SigningKey(usize),
+    Nonces(usize),
+    Commitments(usize),
+    SigningPackage,
+    Transaction,
+    SignatureShare(usize),
+    Signature,
+}
+
+impl Artifact {
+    fn from_file_name(name: &str) -> Option<Self> {
+        let index = |s: &str| s.parse::<usize>().ok();
+        match name {
+            "key.pub" => Some(Self::PublicKey),
+            "round1" => Some(Self::SigningPackage),
+            "round1.tx" => Some(Self::Transaction),
+            "round2" => Some(Self::Signature),
+            _ => {
+                if let Some(rest) = name.strip_prefix("key.") {
+                    index(rest).map(Self::SigningKey)
+                } else if let Some(rest) = name.strip_prefix("round1.") {
+                    if let Some(i) = rest.strip_suffix(".nonces") {
+                        index(i).map(Self::Nonces)
+                    } else {
+                        index(rest.strip_suffix(".commitments")?).map(Self::Commitments)
+                    }
+                } else {
+                    index(name.strip_prefix("round2.")?).map(Self::SignatureShare)
+                }
+            }
+        }
+    }
+}
+
+impl Display for Artifact {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::PublicKey => f.write_str("a public key package"),
+            Self::SigningKey(index) => write!(f, "key share {index}"),
+            Self::Nonces(index) => write!(f, "nonces for share {index}"),
+            Self::Commitments(index) => write!(f, "commitments for share {index}"),
+            Self::SigningPackage => f.write_str("a signing package"),
+            Self::Transaction => f.write_str("a transaction"),
+            Self::SignatureShare(index) => write!(f, "signature share {index}"),
+            Self::Signature => f.write_str("a signature"),
+        }
+    }
+}
+
+/// A message to sign, parsed from a hexadecimal string.
+pub struct Message(pub Vec<u8>);
+
+impl AsRef<[u8]> for Message {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#}", Hex(&self.0))
+    }
+}
+
+impl FromArgValue for Message {
+    fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
+        hex::decode(value)
+            .map(Self)
+            .map_err(|e| format!("invalid message: {e}"))
+    }
+}
+
+/// A command input, either a file path or `-` for standard input.
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// Reads the entire contents of the input.
+    ///
+    /// Standard input is read to its end, so it can only be used for a single
+    /// input of a command, and reading it again is an error.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Stdin => {
+                let mut buf = Vec::new();
+                Self::stdin()?.read_to_end(&mut buf)?;
+                Ok(buf)
+            }
+            Self::File(path) => fs::read(path),
+        }
+    }
+
+    /// Reads the entire contents of an input holding secret material into a
+    /// buffer that is zeroized when dropped.
+    pub fn read_secret(&self) -> io::Result<Zeroizing<Vec<u8>>> {
+        match self {
+            Self::Stdin => {
+                let mut buf = Zeroizing::new(Vec::new());
+                Self::stdin()?.read_to_end(&mut buf)?;
+                Ok(buf)
+            }
+            Self::File(path) => read_secret(path),
+        }
+    }
+
+    fn stdin() -> io::Result<io::StdinLock<'static>> {
+        static READ: AtomicBool = AtomicBool::new(false);
+        if READ.swap(true, Ordering::Relaxed) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "standard input can only be used for one input",
+            ));
+        }
+        Ok(io::stdin().lock())
+    }
+}
+
+impl From<PathBuf> for Input {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl FromArgValue for Input {
+    fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
+        Ok(match value {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+/// A command output, either a file path or `-` for standard output.
+pub enum Output {
+    Stdout,
+    File(PathBuf),
+}
+
+impl Output {
+    /// Writes the contents to the output, replacing existing files.
+    pub fn write(&self, contents: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Stdout => {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(contents)?;
+                stdout.flush()
+            }
+            Self::File(path) => write(path, contents),
+        }
+    }
+
+    /// Writes secret material to the output, replacing existing files.
+    pub fn write_secret(&self, contents: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Stdout => self.write(contents),
+            Self::File(path) => write_secret(path, contents),
+        }
+    }
+}
+
+impl From<PathBuf> for Output {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl FromArgValue for Output {
+    fn from_arg_value(value: &str) -> std::result::Result<Self, String> {
+        Ok(match value {
+            "-" => Self::Stdout,
+            path => Self::File(PathBuf::from(path)),
+        })
+    }
+}
+
+/// Returns the FROST identifier of a share index.
+///
+/// Key shares are generated with the default identifiers, so the share with
+/// index `i` has the identifier `i + 1`.
+pub fn identifier(share_index: usize) -> anyhow::Result<frost::Identifier> {
+    let identifier = u16::try_from(share_index + 1)
+        .map_err(|_| anyhow::anyhow!("share index {share_index} is out of range"))?;
+    Ok(frost::Identifier::try_from(identifier)?)
+}
+
+/// Returns the signer threshold of the selected group, as recorded in the
+/// group index.
+///
+/// The threshold is never read from key shares, so that commands that only
+/// need public information do not touch secret material.
+fn threshold(root: &Root) -> anyhow::Result<Option<u16>> {
+    Ok(match &root.group {
+        Some(group) => groups::threshold(root, group)?,
+        None => None,
+    })
+}
+
+/// Asks for interactive confirmation on the terminal.
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    eprint!("{prompt} [y/N] ");
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Reads a password from an input, without its trailing newline.
+pub fn read_password(input: &Input) -> io::Result<Zeroizing<Vec<u8>>> {
+    let mut password = input.read_secret()?;
+    for suffix in [b'\n', b'\r'] {
+        if password.last() == Some(&suffix) {
+            password.pop();
+        }
     }
+    Ok(password)
+}
+
+/// Atomically writes a file, replacing it if it already exists.
+///
+/// The contents are first written to a temporary file in the same directory,
+/// which is then renamed, so that a crash never leaves a partially written file
+/// behind.
+pub fn write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    rename(write_temporary(path, contents, false)?, path)
+}
+
+/// Atomically writes a new file, erroring if it already exists.
+pub fn write_new(path: &Path, contents: &[u8]) -> io::Result<()> {
+    link(write_temporary(path, contents, false)?, path)
+}
+
+/// Atomically writes a file holding secret material, replacing it if it
+/// already exists. The file is only accessible to the current user.
+pub fn write_secret(path: &Path, contents: &[u8]) -> io::Result<()> {
+    rename(write_temporary(path, contents, true)?, path)
+}
+
+/// Atomically writes a new file holding secret material, erroring if it
+/// already exists. The file is only accessible to the current user.
+pub fn write_secret_new(path: &Path, contents: &[u8]) -> io::Result<()> {
+    link(write_temporary(path, contents, true)?, path)
+}
+
+/// Reads a file holding secret material, warning if it is accessible to
+/// other users. The returned buffer is zeroized when dropped.
+pub fn read_secret(path: &Path) -> io::Result<Zeroizing<Vec<u8>>> {
+    let file = File::open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        if file.metadata()?.permissions().mode() & 0o077 != 0 {
+            eprintln!(
+                "WARNING: {} is accessible to other users, restrict it with `chmod 600`",
+                path.display(),
+            );
+        }
+    }
+    let mut buf = Zeroizing::new(Vec::new());
+    (&file).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Removes a file, optionally overwriting its contents with zeros first.
+///
+/// Note that overwriting is best-effort, as journaling and copy-on-write file
+/// systems as well as SSD wear levelling may keep copies of the original data.
+pub fn remove(path: &Path, overwrite: bool) -> io::Result<()> {
+    if overwrite {
+        let mut file = File::options().write(true).open(path)?;
+        let len = file.metadata()?.len();
+        io::copy(&mut io::repeat(0).take(len), &mut file)?;
+        file.sync_all()?;
+    }
+    fs::remove_file(path)
+}
+
+fn write_temporary(path: &Path, contents: &[u8], secret: bool) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    let temp = path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), process::id()));
+    let mut options = File::options();
+    let _ = fs::remove_file(&temp);
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if secret {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+    let mut file = options.open(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(temp)
+}
+
+fn rename(temp: PathBuf, path: &Path) -> io::Result<()> {
+    fs::rename(&temp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })
+}
+
+fn link(temp: PathBuf, path: &Path) -> io::Result<()> {
+    let result = fs::hard_link(&temp, path);
+    fs::remove_file(&temp)?;
+    result
 }
@@ -1,12 +1,14 @@
 use crate::{
-    cmd::{self, Root},
-    data::round1::CommitmentsPackage,
-    fmt::Hex,
-    hex,
+    cmd::{self, Input, Message, Output, Role, Root, audit, config::Config, json},
+    data::round1::{CommitmentsPackage, SigningPackage},
+    derive,
+    fmt::{Hex, Identifier},
+    rerandomize,
+    tx::Transaction,
 };
-use argh::{FromArgValue, FromArgs};
+use argh::FromArgs;
 use std::{
-    fmt::{self, Display, Formatter},
+    collections::{BTreeMap, BTreeSet},
     fs,
 };
 
@@ -16,51 +18,232 @@ use std::{
 pub struct Command {
     /// the message to sign as a hexadecimal string
     #[argh(option, short = 'm')]
-    message: Message,
-}
+    message: Option<Message>,
 
-struct Message(Vec<u8>);
+    /// a JSON Safe transaction or user operation to attach to the signing
+    /// package, or `-` for standard input; its hash is used as the message to
+    /// sign, so that participants can inspect what they are signing
+    #[argh(option, short = 't')]
+    transaction: Option<Input>,
 
-impl AsRef<[u8]> for Message {
-    fn as_ref(&self) -> &[u8] {
-        &self.0
-    }
-}
+    /// commitments to include, or `-` for standard input; can be specified
+    /// multiple times and defaults to all commitments in the root directory
+    #[argh(option, long = "in")]
+    inputs: Vec<Input>,
 
-impl Display for Message {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{:#}", Hex(&self.0))
-    }
-}
+    /// comma-separated share indexes of the participants to include; defaults
+    /// to all participants with commitments, and commitments of other
+    /// participants are kept for a later session
+    #[argh(option, short = 'p', from_str_fn(parse_participants))]
+    participants: Option<BTreeSet<frost::Identifier>>,
 
-impl FromArgValue for Message {
-    fn from_arg_value(value: &str) -> Result<Self, String> {
-        hex::decode(value)
-            .map(Self)
-            .map_err(|e| format!("invalid message: {e}"))
-    }
+    /// the signer threshold that at least as many participants must be
+    /// included for; defaults to the threshold recorded for the selected group
+    #[argh(option)]
+    threshold: Option<u16>,
+
+    /// where to write the signing package, or `-` for standard output;
+    /// defaults to the root directory
+    #[argh(option, long = "out")]
+    output: Option<Output>,
+
+    /// prepare a re-randomized signing session, where the signing package
+    /// carries a randomizer and the signature is for the randomized key, so
+    /// that it cannot be linked to the group key without the randomizer
+    #[argh(switch)]
+    rerandomize: bool,
+
+    /// the public key package the randomized key is checked against, or `-`
+    /// for standard input; defaults to the public key package in the root
+    /// directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// the non-hardened derivation path of the child key that is signed with
+    /// in a re-randomized session, such as `m/0/1`; defaults to the group key
+    /// itself
+    #[argh(option)]
+    path: Option<derive::Path>,
 }
 
 impl Command {
-    pub fn run(self, root: Root) -> cmd::Result {
-        let commitments = root
-            .all_commitments()?
-            .map(|path| -> anyhow::Result<_> {
-                let data = fs::read(path)?;
-                let commitments = CommitmentsPackage::deserialize(&data)?;
-                Ok((*commitments.identifier(), *commitments.commitments()))
+    pub fn run(self, root: Root, config: &Config) -> cmd::Result {
+        let threshold = match self.threshold {
+            Some(threshold) => threshold,
+            None => cmd::threshold(&root)?
+                .ok_or_else(|| anyhow::anyhow!("signer threshold unknown, specify --threshold"))?,
+        };
+        let root = root.workspace(Role::Coordinator)?;
+        if !self.rerandomize && (self.public_key_package.is_some() || self.path.is_some()) {
+            anyhow::bail!("--public-key-package and --path require --rerandomize");
+        }
+
+        let transaction = self
+            .transaction
+            .map(|input| -> anyhow::Result<_> {
+                Ok(Transaction::deserialize_with(
+                    &input.read()?,
+                    &config.transaction_defaults(),
+                )?)
             })
-            .collect::<Result<_, _>>()?;
-        let signing = frost::SigningPackage::new(commitments, self.message.as_ref());
+            .transpose()?;
+        let message = match (self.message, &transaction) {
+            (Some(message), None) => message.0,
+            (message, Some(transaction)) => {
+                let hash = transaction.hash()?;
+                if let Some(message) = message
+                    && message.0 != hash
+                {
+                    anyhow::bail!(
+                        "transaction hash {:#} does not match the message {message}",
+                        Hex(&hash),
+                    );
+                }
+                hash.to_vec()
+            }
+            (None, None) => anyhow::bail!("either a message or a transaction must be specified"),
+        };
+
+        // Only commitments from the root directory are cleaned up, explicitly
+        // specified inputs are left untouched.
+        let from_root = self.inputs.is_empty();
+        let inputs = if from_root {
+            root.all_commitments()?.map(Input::from).collect()
+        } else {
+            self.inputs
+        };
 
-        fs::write(root.signing_package(), signing.serialize()?)?;
+        let mut commitments = BTreeMap::new();
+        let mut used = Vec::new();
+        for input in inputs {
+            let mut unused = false;
+            for package in CommitmentsPackage::deserialize_all(&input.read()?)? {
+                let identifier = *package.identifier();
+                if self
+                    .participants
+                    .as_ref()
+                    .is_some_and(|participants| !participants.contains(&identifier))
+                {
+                    unused = true;
+                    continue;
+                }
+                if commitments
+                    .insert(identifier, *package.commitments())
+                    .is_some()
+                {
+                    anyhow::bail!(
+                        "duplicate commitments for participant {}",
+                        Identifier(&identifier),
+                    );
+                }
+            }
+            if !unused {
+                used.push(input);
+            }
+        }
+
+        if let Some(participants) = &self.participants {
+            for identifier in participants {
+                if !commitments.contains_key(identifier) {
+                    anyhow::bail!("no commitments for participant {}", Identifier(identifier));
+                }
+            }
+        }
+        let threshold = usize::from(threshold);
+        if commitments.len() < threshold {
+            anyhow::bail!(
+                "signing needs at least {threshold} participants, got {}",
+                commitments.len(),
+            );
+        }
+        if commitments.len() > threshold {
+            eprintln!(
+                "WARNING: signing needs only {threshold} participants, got {}",
+                commitments.len(),
+            );
+        }
+
+        let signing = frost::SigningPackage::new(commitments, &message);
+        let randomizer = if self.rerandomize {
+            let input = self
+                .public_key_package
+                .unwrap_or_else(|| root.public_key().into());
+            let mut pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+            if let Some(path) = &self.path {
+                pubkey = derive::public_key_package(&pubkey, path);
+            }
+            Some(rerandomize::randomizer(pubkey.verifying_key(), &signing)?)
+        } else {
+            None
+        };
+        let package = SigningPackage::new(signing, randomizer);
+        let data = package.serialize()?;
+        let signing = package.signing();
+
+        root.ensure()?;
+        match self.output {
+            Some(output) => output.write(&data)?,
+            None => {
+                // Attach the transaction to the signing package in the root
+                // directory, making sure to not leave a stale transaction from
+                // a previous signing session behind.
+                cmd::write(&root.signing_package(), &data)?;
+                match &transaction {
+                    Some(transaction) => {
+                        cmd::write(&root.transaction(), &transaction.serialize()?)?
+                    }
+                    None if root.transaction().exists() => fs::remove_file(root.transaction())?,
+                    None => {}
+                }
+            }
+        }
 
         // Clean up the commitments after generating the signing package, as
-        // they are no longer needed.
-        for path in root.all_commitments()? {
-            fs::remove_file(path)?;
+        // they are no longer needed. Commitments of participants that were not
+        // included are kept, so that they can be used for a later session.
+        if from_root {
+            for input in used {
+                if let Input::File(path) = input {
+                    fs::remove_file(path)?;
+                }
+            }
         }
 
+        audit::record(
+            &root,
+            &audit::Event::Prepare {
+                message: format!("{:#}", Hex(&message)),
+                participants: signing
+                    .signing_commitments()
+                    .keys()
+                    .copied()
+                    .map(json::Identifier)
+                    .collect(),
+                commitments: signing
+                    .signing_commitments()
+                    .values()
+                    .map(|commitments| Ok(json::fingerprint(&commitments.serialize()?)))
+                    .collect::<Result<_, frost::Error>>()?,
+                signing_package: json::fingerprint(&data),
+            },
+        )?;
+
         Ok(())
     }
 }
+
+fn parse_participants(value: &str) -> Result<BTreeSet<frost::Identifier>, String> {
+    let mut participants = BTreeSet::new();
+    for participant in value.split(',') {
+        let identifier = participant
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|share_index| cmd::identifier(share_index).ok())
+            .ok_or_else(|| format!("invalid participant '{participant}'"))?;
+        if !participants.insert(identifier) {
+            return Err(format!("duplicate participant '{participant}'"));
+        }
+    }
+    Ok(participants)
+}
@@ -0,0 +1,89 @@
+use crate::{
+    address::Address,
+    cmd::{self, Input, Output, Role, Root, split::RootKey},
+    fmt::Hex,
+    keystore,
+};
+use argh::FromArgs;
+use zeroize::Zeroizing;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "reconstruct")]
+/// reconstruct the root secret key from signing shares
+pub struct Command {
+    /// key shares to reconstruct the secret key from, or `-` for standard
+    /// input; can be specified multiple times and defaults to all key shares
+    /// in the root directory
+    #[argh(option, long = "in")]
+    inputs: Vec<Input>,
+
+    /// the public key package to check the reconstructed secret key against,
+    /// or `-` for standard input; defaults to the public key package in the
+    /// root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// where to write the secret key, or `-` for standard output
+    #[argh(option, long = "out")]
+    output: Output,
+
+    /// write the secret key as an Ethereum V3 keystore encrypted with the
+    /// password from `--password-file`, instead of as a hexadecimal string
+    #[argh(switch)]
+    keystore: bool,
+
+    /// a file with the keystore password, or `-` for standard input
+    #[argh(option)]
+    password_file: Option<Input>,
+}
+
+impl Command {
+    pub fn run(self, root: Root) -> cmd::Result {
+        let root = root.workspace(Role::Dealer)?;
+        let password = match (self.keystore, &self.password_file) {
+            (true, Some(input)) => Some(cmd::read_password(input)?),
+            (true, None) => anyhow::bail!("--keystore requires a --password-file"),
+            (false, Some(_)) => anyhow::bail!("--password-file requires --keystore"),
+            (false, None) => None,
+        };
+
+        let input = self
+            .public_key_package
+            .unwrap_or_else(|| root.public_key().into());
+        let pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+
+        let inputs = if self.inputs.is_empty() {
+            root.all_signing_keys()?.map(Input::from).collect()
+        } else {
+            self.inputs
+        };
+        let mut keys = Zeroizing::new(Vec::new());
+        for input in &inputs {
+            keys.push(frost::keys::KeyPackage::deserialize(&input.read_secret()?)?);
+        }
+
+        let secret = RootKey::reconstruct(&keys)?;
+        let key = secret.verifying_key();
+        if &key != pubkey.verifying_key() {
+            anyhow::bail!("reconstructed secret key does not match the public key package");
+        }
+
+        let secret = Zeroizing::new(secret.signing_key().serialize());
+        let contents = match &password {
+            Some(password) => {
+                let mut keystore = Zeroizing::new(keystore::encrypt(
+                    &secret,
+                    Address::from_key(&key),
+                    password,
+                    &mut rand::thread_rng(),
+                )?);
+                keystore.push(b'\n');
+                keystore
+            }
+            None => Zeroizing::new(format!("{:#}\n", Hex(&secret)).into_bytes()),
+        };
+        self.output.write_secret(&contents)?;
+
+        Ok(())
+    }
+}
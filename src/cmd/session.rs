@@ -0,0 +1,82 @@
+use crate::{
+    cmd::{Input, Root},
+    data::round1::SigningPackage,
+    fmt::{Hex, Identifier},
+    rerandomize::Randomizer,
+    tx::{self, Transaction},
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A signing session, consisting of a signing package, the randomizer of a
+/// re-randomized session and the transaction it signs, if known.
+pub struct Session {
+    pub signing: frost::SigningPackage,
+    pub randomizer: Option<Randomizer>,
+    pub transaction: Option<Transaction>,
+}
+
+impl Session {
+    /// Loads a signing session.
+    ///
+    /// The transaction is read from the explicitly specified input or, when
+    /// the signing package is read from the root directory, from the
+    /// transaction attached to it there. An error is returned if the
+    /// transaction does not hash to the signing message. Fields that the
+    /// transaction omits are filled in from the specified defaults.
+    pub fn load(
+        root: &Root,
+        signing: Option<Input>,
+        transaction: Option<Input>,
+        defaults: &tx::Defaults,
+    ) -> anyhow::Result<Self> {
+        let from_root = signing.is_none();
+        let input = signing.unwrap_or_else(|| root.signing_package().into());
+        let package = SigningPackage::deserialize(&input.read()?)?;
+        let (signing, randomizer) = (package.signing().clone(), package.randomizer());
+
+        let transaction = match transaction {
+            Some(input) => Some(input),
+            None if from_root && root.transaction().exists() => Some(root.transaction().into()),
+            None => None,
+        };
+        let transaction = transaction
+            .map(|input| -> anyhow::Result<_> {
+                Ok(Transaction::deserialize_with(&input.read()?, defaults)?)
+            })
+            .transpose()?;
+
+        if let Some(transaction) = &transaction {
+            let hash = transaction.hash()?;
+            if hash.as_slice() != signing.message() {
+                anyhow::bail!(
+                    "transaction hash {:#} does not match the signing message {:#}",
+                    Hex(&hash),
+                    Hex(signing.message()),
+                );
+            }
+        }
+
+        Ok(Self {
+            signing,
+            randomizer,
+            transaction,
+        })
+    }
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "message:      {:#}", Hex(self.signing.message()))?;
+        write!(f, "participants:")?;
+        for identifier in self.signing.signing_commitments().keys() {
+            write!(f, " {}", Identifier(identifier))?;
+        }
+        if self.randomizer.is_some() {
+            write!(f, "\nrerandomized: yes")?;
+        }
+        if let Some(transaction) = &self.transaction {
+            write!(f, "\n{transaction}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,23 @@
 use crate::{
-    cmd::{self, Root},
+    address::Address,
+    cmd::{
+        self, Input, Message, Output, Role, Root, audit, config::Config, json, session::Session,
+        split,
+    },
     data::round2::SignatureSharePackage,
+    derive, evm,
+    fmt::Hex,
+    keccak, rerandomize,
+    tx::Policy,
 };
 use argh::FromArgs;
-use std::fs;
+use std::{
+    fs::File,
+    io::{self, BufRead as _, BufReader, IsTerminal as _, Write as _},
+    path::Path,
+    str,
+};
+use zeroize::Zeroizing;
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "sign")]
@@ -11,29 +25,249 @@ use std::fs;
 pub struct Command {
     /// share index
     #[argh(option, short = 'i')]
-    share_index: usize,
+    share_index: Option<usize>,
+
+    /// a file with the hexadecimal root secret key, or `-` for standard input,
+    /// to directly sign a message without a signing ritual and write the FROST
+    /// signature to the root directory; intended for testing only
+    #[argh(option)]
+    root_key: Option<Input>,
+
+    /// the message to sign with the root secret key as a hexadecimal string
+    #[argh(option, short = 'm')]
+    message: Option<Message>,
+
+    /// the signing package to sign, or `-` for standard input; defaults to the
+    /// signing package in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// where to write the signature share, or the signature with the root
+    /// secret key, or `-` for standard output; defaults to the root directory
+    #[argh(option, long = "out")]
+    output: Option<Output>,
+
+    /// the JSON Safe transaction or user operation being signed, or `-` for
+    /// standard input; defaults to the transaction attached to the signing
+    /// package in the root directory, if any
+    #[argh(option, short = 't')]
+    transaction: Option<Input>,
+
+    /// the message that is expected to be signed as a hexadecimal string,
+    /// signing is refused if the signing package is for a different message
+    #[argh(option)]
+    expect_message: Option<Message>,
+
+    /// a JSON signing policy that the transaction attached to the signing
+    /// session is evaluated against, signing is refused if the policy does not
+    /// allow it and approved without a prompt if it does; defaults to the
+    /// policy of the configuration, if any
+    #[argh(option)]
+    policy: Option<Input>,
+
+    /// the non-hardened derivation path of the child key to sign with, such as
+    /// `m/0/1`; defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// approve signing without an interactive confirmation prompt
+    #[argh(switch, short = 'y')]
+    yes: bool,
+
+    /// overwrite the nonces with zeros before deleting them
+    #[argh(switch)]
+    shred: bool,
 }
 
 impl Command {
-    pub fn run(self, root: Root) -> cmd::Result {
-        let data = fs::read(root.signing_key(self.share_index))?;
-        let key = frost::keys::KeyPackage::deserialize(&data)?;
+    pub fn run(self, root: Root, config: &Config) -> cmd::Result {
+        let share_index = match (self.share_index, &self.root_key) {
+            (Some(_), Some(_)) => anyhow::bail!("--share-index and --root-key cannot be combined"),
+            (Some(share_index), None) => share_index,
+            (None, Some(_)) => return self.run_root_key(root),
+            (None, None) => anyhow::bail!("either --share-index or --root-key is required"),
+        };
+        if self.message.is_some() {
+            anyhow::bail!("--message requires --root-key, use --expect-message instead");
+        }
+
+        let root = root.workspace(Role::Signer(share_index))?;
+        let policy = self
+            .policy
+            .or_else(|| config.policy.clone().map(Input::from));
+        let data = cmd::read_secret(&root.signing_key(share_index))?;
+        let mut key = Zeroizing::new(frost::keys::KeyPackage::deserialize(&data)?);
+        if let Some(path) = &self.path {
+            key = Zeroizing::new(derive::key_package(&key, path));
+        }
+
+        let interactive = !matches!(self.input, Some(Input::Stdin))
+            && !matches!(self.transaction, Some(Input::Stdin))
+            && !matches!(policy, Some(Input::Stdin))
+            && io::stdin().is_terminal();
+        let session = Session::load(
+            &root,
+            self.input,
+            self.transaction,
+            &config.transaction_defaults(),
+        )?;
+        let signing = &session.signing;
 
-        let data = fs::read(root.nonces(self.share_index))?;
-        let nonces = frost::round1::SigningNonces::deserialize(&data)?;
+        // Never sign blindly: show what is being signed, and require explicit
+        // approval of it.
+        eprintln!("{session}");
+        if let Some(path) = &self.path {
+            eprintln!(
+                "derived key {path}: {}",
+                Address::from_key(key.verifying_key())
+            );
+        }
+        if let Some(randomizer) = session.randomizer {
+            // The randomizer is chosen by the coordinator, so make sure that the
+            // signature will be for a key that can be verified on-chain.
+            let randomized = rerandomize::randomized_key(key.verifying_key(), randomizer);
+            evm::verified_key(&randomized)?;
+            eprintln!("randomized key: {}", Address::from_key(&randomized));
+        }
+        let mut approved = self.yes;
+        if let Some(policy) = &policy {
+            let policy = Policy::deserialize(&policy.read()?)?;
+            let Some(transaction) = &session.transaction else {
+                anyhow::bail!(
+                    "signing policy requires a transaction attached to the signing session"
+                );
+            };
+            let reasons = policy.evaluate(transaction);
+            if !reasons.is_empty() {
+                anyhow::bail!(
+                    "transaction is refused by the signing policy:\n  - {}",
+                    reasons.join("\n  - "),
+                );
+            }
+            approved = true;
+        }
+        if let Some(expected) = &self.expect_message {
+            if &expected.0 != signing.message() {
+                anyhow::bail!("signing package is not for the expected message {expected}");
+            }
+            approved = true;
+        }
+        if !approved {
+            if !interactive {
+                anyhow::bail!(
+                    "refusing to sign without approval, use --expect-message, --policy or --yes"
+                );
+            }
+            if !cmd::confirm("sign this message?")? {
+                anyhow::bail!("signing was not approved");
+            }
+        }
 
-        let data = fs::read(root.signing_package())?;
-        let signing = frost::SigningPackage::deserialize(&data)?;
+        // Hold the lock for the entire duration that the nonces are in use, so
+        // that concurrent invocations can never sign with the same nonces.
+        let _lock = root.lock()?;
 
-        let signature = frost::round2::sign(&signing, &nonces, &key)?;
+        let data = cmd::read_secret(&root.nonces(share_index))?;
+        let nonces = Zeroizing::new(frost::round1::SigningNonces::deserialize(&data)?);
+
+        // Record the nonces as consumed _before_ signing, so that they are
+        // refused even if the signer crashes right after producing the share,
+        // or the nonces file is later restored from a backup.
+        let fingerprint = format!("{}", Hex(&keccak::v256(&nonces.commitments().serialize()?)));
+        let ledger = root.consumed_nonces();
+        if is_consumed(&ledger, &fingerprint)? {
+            anyhow::bail!(
+                "nonces for share {} were already used for signing, refusing to reuse them",
+                share_index,
+            );
+        }
+        consume(&ledger, &fingerprint)?;
+
+        let signature = match session.randomizer {
+            Some(randomizer) => frost_rerandomized::sign(signing, &nonces, &key, randomizer)?,
+            None => frost::round2::sign(signing, &nonces, &key)?,
+        };
         let share = SignatureSharePackage::new(*key.identifier(), signature);
 
-        fs::write(root.signature_share(self.share_index), share.serialize()?)?;
+        self.output
+            .unwrap_or_else(|| root.signature_share(share_index).into())
+            .write(&share.serialize()?)?;
+        audit::record(
+            &root,
+            &audit::Event::Sign {
+                participant: json::Identifier(*key.identifier()),
+                message: format!("{:#}", Hex(signing.message())),
+                commitments: json::fingerprint(&nonces.commitments().serialize()?),
+                signing_package: json::fingerprint(&signing.serialize()?),
+                path: self.path.as_ref().map(ToString::to_string),
+            },
+        )?;
 
         // To avoid accidentally re-using nonces (which would be CATASTROPHIC),
         // we delete it after signing.
-        fs::remove_file(root.nonces(self.share_index))?;
+        cmd::remove(&root.nonces(share_index), self.shred)?;
 
         Ok(())
     }
+
+    /// Signs a message with the root secret key, producing the same FROST
+    /// signature that a signing ritual of the key's shares would.
+    fn run_root_key(self, root: Root) -> cmd::Result {
+        if self.input.is_some()
+            || self.transaction.is_some()
+            || self.expect_message.is_some()
+            || self.policy.is_some()
+            || self.path.is_some()
+            || self.yes
+            || self.shred
+        {
+            anyhow::bail!(
+                "--root-key cannot be combined with --in, --transaction, --expect-message, \
+                 --policy, --path, --yes or --shred"
+            );
+        }
+        let (Some(input), Some(message)) = (&self.root_key, &self.message) else {
+            anyhow::bail!("--root-key requires a --message to sign");
+        };
+        let root = root.workspace(Role::Coordinator)?;
+
+        let data = input.read_secret()?;
+        let key =
+            split::parse_root_key(str::from_utf8(&data)?.trim()).map_err(anyhow::Error::msg)?;
+        let public_key = key.verifying_key();
+        evm::verified_key(&public_key)?;
+        eprintln!("message: {message}");
+        eprintln!("address: {}", Address::from_key(&public_key));
+
+        let signature = key.signing_key().sign(rand::thread_rng(), &message.0);
+        root.ensure()?;
+        self.output
+            .unwrap_or_else(|| root.signature().into())
+            .write(&signature.serialize()?)?;
+
+        Ok(())
+    }
+}
+
+/// Returns whether or not nonces with the specified commitment fingerprint
+/// are recorded in the consumed nonce ledger.
+fn is_consumed(ledger: &Path, fingerprint: &str) -> io::Result<bool> {
+    let file = match File::open(ledger) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err),
+    };
+    for line in BufReader::new(file).lines() {
+        if line? == fingerprint {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Appends a commitment fingerprint to the consumed nonce ledger.
+fn consume(ledger: &Path, fingerprint: &str) -> io::Result<()> {
+    let mut file = File::options().create(true).append(true).open(ledger)?;
+    writeln!(file, "{fingerprint}")?;
+    file.sync_all()
 }
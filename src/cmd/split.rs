@@ -1,13 +1,27 @@
 use crate::{
-    cmd::{self, Root},
-    evm, hex,
+    address::Address,
+    bip32,
+    cmd::{self, Input, Layout, Role, Root, audit, groups, json},
+    evm,
+    fmt::Hex,
+    hex, keystore,
 };
 use argh::FromArgs;
+use rand::{CryptoRng, RngCore};
 use std::{
-    fs::File,
-    io::{self, Write as _},
+    hint,
+    io::{self, IsTerminal as _},
+    num::NonZeroUsize,
     path::PathBuf,
+    str,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
+use zeroize::Zeroizing;
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "split")]
@@ -15,7 +29,45 @@ use std::{
 pub struct Command {
     /// secret key, leave empty to generate a random one
     #[argh(option, short = 'k', from_str_fn(parse_root_key))]
-    secret_key: Option<frost::SigningKey>,
+    secret_key: Option<RootKey>,
+
+    /// a file with the hexadecimal secret key, or `-` for standard input, so
+    /// that it does not leak into the shell history
+    #[argh(option)]
+    secret_key_file: Option<Input>,
+
+    /// an Ethereum V3 keystore file with the secret key, or `-` for standard
+    /// input
+    #[argh(option)]
+    keystore: Option<Input>,
+
+    /// a file with the keystore password, or `-` for standard input
+    #[argh(option)]
+    password_file: Option<Input>,
+
+    /// a file with a BIP-39 mnemonic to derive the secret key from, or `-`
+    /// for standard input
+    #[argh(option)]
+    mnemonic_file: Option<Input>,
+
+    /// a file with the BIP-39 passphrase of the mnemonic, or `-` for standard
+    /// input; defaults to an empty passphrase
+    #[argh(option)]
+    passphrase_file: Option<Input>,
+
+    /// the BIP-32 derivation path of the mnemonic account; defaults to
+    /// `m/44'/60'/0'/0/0`
+    #[argh(option)]
+    derivation_path: Option<bip32::Path>,
+
+    /// the address that the mnemonic account is expected to have, splitting is
+    /// refused if it is different
+    #[argh(option)]
+    expect_address: Option<Address>,
+
+    /// confirm splitting the mnemonic account without an interactive prompt
+    #[argh(switch, short = 'y')]
+    yes: bool,
 
     /// signer threshold
     #[argh(option, short = 't', default = "3")]
@@ -29,21 +81,64 @@ pub struct Command {
     /// signing key share would overwrite an existing file
     #[argh(switch, short = 'f')]
     force: bool,
+
+    /// additionally export a self-contained bundle for each signer, with their
+    /// key share and the public key, to `signer-${index}/` workspaces, as well
+    /// as the public key to the `coordinator/` workspace
+    #[argh(switch)]
+    export_bundles: bool,
+
+    /// generate random root keys until their address starts with the
+    /// specified hexadecimal digits
+    #[argh(option, from_str_fn(parse_address_pattern))]
+    address_prefix: Option<String>,
+
+    /// generate random root keys until their address ends with the specified
+    /// hexadecimal digits
+    #[argh(option, from_str_fn(parse_address_pattern))]
+    address_suffix: Option<String>,
+
+    /// the number of threads to search for a root key address with; defaults
+    /// to the available parallelism
+    #[argh(option)]
+    threads: Option<NonZeroUsize>,
 }
 
 impl Command {
     pub fn run(self, root: Root) -> cmd::Result {
+        // Root keys are only ever borrowed, so that they are not copied.
+        let read = self.read_secret_key()?;
+        let searched;
+        let secret_key = match read.as_ref().or(self.secret_key.as_ref()) {
+            Some(_) if self.address_prefix.is_some() || self.address_suffix.is_some() => {
+                anyhow::bail!(
+                    "a secret key cannot be combined with --address-prefix or --address-suffix"
+                );
+            }
+            Some(secret_key) => Some(secret_key),
+            None if self.address_prefix.is_some() || self.address_suffix.is_some() => {
+                searched = self.search()?;
+                Some(&searched)
+            }
+            None => None,
+        };
+
         let mut shares;
         let mut pubkey_package;
 
         while {
             let mut rng = rand::thread_rng();
-            let secret = self
-                .secret_key
-                .unwrap_or_else(|| frost::SigningKey::new(&mut rng));
+            let generated;
+            let secret = match secret_key {
+                Some(secret) => secret,
+                None => {
+                    generated = RootKey::generate(&mut rng);
+                    &generated
+                }
+            };
 
             (shares, pubkey_package) = frost::keys::split(
-                &secret,
+                secret.signing_key(),
                 self.signers,
                 self.threshold,
                 frost::keys::IdentifierList::Default,
@@ -52,29 +147,303 @@ impl Command {
             evm::verified_public_key(&pubkey_package).is_err()
         } {}
 
-        root.ensure()?;
-        self.write(root.public_key(), &pubkey_package.serialize()?)?;
+        let address = Address::from_key(evm::verified_public_key(&pubkey_package)?);
+
+        let dealer = root.workspace(Role::Dealer)?;
+        dealer.ensure()?;
+        let pubkey_package = pubkey_package.serialize()?;
+        self.write(dealer.public_key(), &pubkey_package)?;
         for (index, (_, share)) in shares.into_iter().enumerate() {
-            let key_package = frost::keys::KeyPackage::try_from(share)?;
-            self.write(root.signing_key(index), &key_package.serialize()?)?;
+            let key_package = Zeroizing::new(frost::keys::KeyPackage::try_from(share)?);
+            let key_package = Zeroizing::new(key_package.serialize()?);
+            self.write_secret(dealer.signing_key(index), &key_package)?;
+
+            if self.export_bundles {
+                let bundle = root
+                    .clone()
+                    .with_layout(Layout::Roles)
+                    .workspace_unchecked(Role::Signer(index));
+                bundle.ensure()?;
+                self.write(bundle.public_key(), &pubkey_package)?;
+                self.write_secret(bundle.signing_key(index), &key_package)?;
+            }
+        }
+        if self.export_bundles {
+            let coordinator = root
+                .clone()
+                .with_layout(Layout::Roles)
+                .workspace_unchecked(Role::Coordinator);
+            coordinator.ensure()?;
+            self.write(coordinator.public_key(), &pubkey_package)?;
+        }
+        if let Some(group) = &root.group {
+            groups::record(&root, group, address, self.threshold, self.signers)?;
         }
+        audit::record(
+            &dealer,
+            &audit::Event::Split {
+                address,
+                threshold: self.threshold,
+                signers: self.signers,
+                public_key_package: json::fingerprint(&pubkey_package),
+            },
+        )?;
 
         Ok(())
     }
 
-    fn write(&self, path: PathBuf, contents: &[u8]) -> Result<(), io::Error> {
-        let mut file = if self.force {
-            File::create(path)?
+    /// Reads the specified secret key, if any, from any of the supported
+    /// sources other than `--secret-key`.
+    fn read_secret_key(&self) -> anyhow::Result<Option<RootKey>> {
+        let sources = [
+            self.secret_key.is_some(),
+            self.secret_key_file.is_some(),
+            self.keystore.is_some(),
+            self.mnemonic_file.is_some(),
+        ];
+        if sources.into_iter().filter(|&source| source).count() > 1 {
+            anyhow::bail!(
+                "only one of --secret-key, --secret-key-file, --keystore or --mnemonic-file can \
+                 be specified"
+            );
+        }
+
+        if let Some(input) = &self.secret_key_file {
+            let data = input.read_secret()?;
+            let key = parse_root_key(str::from_utf8(&data)?.trim()).map_err(anyhow::Error::msg)?;
+            Ok(Some(key))
+        } else if let Some(input) = &self.keystore {
+            self.decrypt_keystore(input).map(Some)
+        } else if let Some(input) = &self.mnemonic_file {
+            self.derive_mnemonic(input).map(Some)
         } else {
-            File::create_new(path)?
+            Ok(None)
+        }
+    }
+
+    /// Derives the secret key of a BIP-39 mnemonic account, and requires
+    /// confirmation of its address.
+    fn derive_mnemonic(&self, input: &Input) -> anyhow::Result<RootKey> {
+        if matches!(
+            (input, &self.passphrase_file),
+            (Input::Stdin, Some(Input::Stdin))
+        ) {
+            anyhow::bail!(
+                "--mnemonic-file and --passphrase-file cannot both be read from standard input"
+            );
+        }
+        let phrase = input.read_secret()?;
+        let mnemonic =
+            bip39::Mnemonic::from_phrase(str::from_utf8(&phrase)?, bip39::Language::English)
+                .map_err(|e| anyhow::anyhow!("invalid mnemonic: {e}"))?;
+        let passphrase = match &self.passphrase_file {
+            Some(input) => cmd::read_password(input)?,
+            None => Zeroizing::new(Vec::new()),
         };
-        file.write_all(contents)?;
-        Ok(())
+        let seed = bip39::Seed::new(&mnemonic, str::from_utf8(&passphrase)?);
+
+        let default_path = bip32::Path::default();
+        let path = self.derivation_path.as_ref().unwrap_or(&default_path);
+        let secret = bip32::derive(seed.as_bytes(), path)?;
+        let key = RootKey::deserialize(secret.as_slice())?;
+
+        let address = Address::from_key(&key.verifying_key());
+        eprintln!("mnemonic account {path}: {address}");
+        if let Some(expected) = &self.expect_address {
+            if *expected != address {
+                anyhow::bail!("mnemonic account is not the expected address {expected}");
+            }
+        } else if !self.yes {
+            let interactive = !matches!(input, Input::Stdin)
+                && !matches!(self.passphrase_file, Some(Input::Stdin))
+                && io::stdin().is_terminal();
+            if !interactive {
+                anyhow::bail!(
+                    "refusing to split without confirmation, use --expect-address or --yes"
+                );
+            }
+            if !cmd::confirm("split the secret key of this account?")? {
+                anyhow::bail!("splitting was not confirmed");
+            }
+        }
+        Ok(key)
+    }
+
+    /// Decrypts the secret key of a V3 keystore, checking that it matches the
+    /// address recorded in the keystore.
+    fn decrypt_keystore(&self, input: &Input) -> anyhow::Result<RootKey> {
+        let Some(password) = &self.password_file else {
+            anyhow::bail!("--keystore requires a --password-file");
+        };
+        if matches!((input, password), (Input::Stdin, Input::Stdin)) {
+            anyhow::bail!("--keystore and --password-file cannot both be read from standard input");
+        }
+        let password = cmd::read_password(password)?;
+        let (secret, address) = keystore::decrypt(&input.read()?, &password)?;
+        let key = RootKey::deserialize(&secret)?;
+
+        let actual = Address::from_key(&key.verifying_key());
+        if let Some(address) = address
+            && address != actual
+        {
+            anyhow::bail!("keystore address {address} does not match its secret key {actual}");
+        }
+        Ok(key)
+    }
+
+    /// Searches for a random root key with an address matching the prefix and
+    /// suffix, reporting progress along the way.
+    fn search(&self) -> anyhow::Result<RootKey> {
+        let prefix = self.address_prefix.as_deref().unwrap_or_default();
+        let suffix = self.address_suffix.as_deref().unwrap_or_default();
+        if prefix.len() + suffix.len() > 40 {
+            anyhow::bail!("address prefix and suffix are longer than an address");
+        }
+        let threads = match self.threads {
+            Some(threads) => threads,
+            None => thread::available_parallelism()?,
+        };
+
+        // Each hexadecimal digit of the pattern matches one in 16 addresses.
+        let expected = 16_f64.powi((prefix.len() + suffix.len()) as _);
+        eprintln!(
+            "searching for an address 0x{prefix}...{suffix} with {threads} threads, \
+             expecting {expected:.0} attempts",
+        );
+
+        let start = Instant::now();
+        let attempts = AtomicU64::new(0);
+        let found = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+        let key = thread::scope(|scope| {
+            for _ in 0..threads.get() {
+                let sender = sender.clone();
+                let (attempts, found) = (&attempts, &found);
+                scope.spawn(move || {
+                    let mut rng = rand::thread_rng();
+                    while !found.load(Ordering::Relaxed) {
+                        let secret = RootKey::generate(&mut rng);
+                        attempts.fetch_add(1, Ordering::Relaxed);
+                        let key = secret.verifying_key();
+                        if evm::verified_key(&key).is_err() {
+                            continue;
+                        }
+                        let address = format!("{}", Hex(Address::from_key(&key).as_slice()));
+                        if address.starts_with(prefix) && address.ends_with(suffix) {
+                            found.store(true, Ordering::Relaxed);
+                            let _ = sender.send(secret);
+                        }
+                    }
+                });
+            }
+
+            loop {
+                match receiver.recv_timeout(Duration::from_secs(5)) {
+                    Ok(secret) => {
+                        found.store(true, Ordering::Relaxed);
+                        break secret;
+                    }
+                    Err(_) => {
+                        let attempts = attempts.load(Ordering::Relaxed) as f64;
+                        let rate = attempts / start.elapsed().as_secs_f64();
+                        eprintln!(
+                            "{attempts:.0} attempts ({rate:.0}/s), expected to take about {:.0}s",
+                            expected / rate,
+                        );
+                    }
+                }
+            }
+        });
+
+        eprintln!(
+            "found address {} after {} attempts in {:.1}s",
+            Address::from_key(&key.verifying_key()),
+            attempts.load(Ordering::Relaxed),
+            start.elapsed().as_secs_f64(),
+        );
+        Ok(key)
+    }
+
+    fn write(&self, path: PathBuf, contents: &[u8]) -> Result<(), io::Error> {
+        if self.force {
+            cmd::write(&path, contents)
+        } else {
+            cmd::write_new(&path, contents)
+        }
+    }
+
+    fn write_secret(&self, path: PathBuf, contents: &[u8]) -> Result<(), io::Error> {
+        if self.force {
+            cmd::write_secret(&path, contents)
+        } else {
+            cmd::write_secret_new(&path, contents)
+        }
+    }
+}
+
+fn parse_address_pattern(value: &str) -> Result<String, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.is_empty() || digits.len() > 40 {
+        return Err("invalid address pattern: expected 1 to 40 hexadecimal digits".to_owned());
+    }
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("invalid address pattern: expected hexadecimal digits".to_owned());
     }
+    Ok(digits.to_ascii_lowercase())
 }
 
-fn parse_root_key(value: &str) -> Result<frost::SigningKey, String> {
-    let secret = hex::decode::<[u8; 32]>(value).map_err(|e| format!("invalid secret: {e}"))?;
-    let key = frost::SigningKey::deserialize(&secret).map_err(|e| format!("invalid key: {e}"))?;
+pub fn parse_root_key(value: &str) -> Result<RootKey, String> {
+    let secret = hex::decode::<[u8; 32]>(value)
+        .map(Zeroizing::new)
+        .map_err(|e| format!("invalid secret: {e}"))?;
+    let key = RootKey::deserialize(secret.as_slice()).map_err(|e| format!("invalid key: {e}"))?;
     Ok(key)
 }
+
+/// A root secret key, which is overwritten when dropped.
+///
+/// `frost::SigningKey` is `Copy` and does not implement `Zeroize`, so root keys
+/// are kept in this wrapper, which is neither `Copy` nor `Clone`, and are only
+/// borrowed from it. Overwriting the key when dropped is best-effort: without
+/// `Zeroize` support, the store may still be elided by the compiler, and
+/// copies made by `frost` while generating or reconstructing the key are not
+/// wiped.
+pub struct RootKey(frost::SigningKey);
+
+impl RootKey {
+    /// Generates a random root key.
+    pub fn generate(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        Self(frost::SigningKey::new(rng))
+    }
+
+    /// Reconstructs a root key from key shares.
+    pub fn reconstruct(keys: &[frost::keys::KeyPackage]) -> Result<Self, frost::Error> {
+        frost::keys::reconstruct(keys).map(Self)
+    }
+
+    /// Deserializes a root key from its 32-byte big-endian secret scalar.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, frost::Error> {
+        frost::SigningKey::deserialize(bytes).map(Self)
+    }
+
+    /// Returns the FROST signing key.
+    pub fn signing_key(&self) -> &frost::SigningKey {
+        &self.0
+    }
+
+    /// Returns the public key of the root key.
+    pub fn verifying_key(&self) -> frost::VerifyingKey {
+        frost::VerifyingKey::from(&self.0)
+    }
+}
+
+impl Drop for RootKey {
+    fn drop(&mut self) {
+        // Overwrite the secret scalar with the public scalar one, and hint to
+        // the compiler that the store is observed so that it is not elided.
+        if let Ok(key) = frost::SigningKey::from_scalar(k256::Scalar::ONE) {
+            self.0 = key;
+        }
+        hint::black_box(&mut self.0);
+    }
+}
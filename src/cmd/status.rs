@@ -0,0 +1,430 @@
+use crate::{
+    address::Address,
+    cmd::{self, Format, Role, Root, config::Config, json},
+    data::round1::SigningPackage,
+    derive,
+    fmt::{Hex, Identifier},
+    rerandomize,
+};
+use argh::FromArgs;
+use frost::serde::Serialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+};
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "status")]
+/// display the progress of the signing session and the next step of each role
+pub struct Command {
+    /// the non-hardened derivation path of the child key the session signs
+    /// with, such as `m/0/1`; defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// output in JSON format, intended for use with other tooling; the
+    /// default with the `json` output format
+    #[argh(switch, short = 'j')]
+    json: bool,
+}
+
+impl Command {
+    pub fn run(self, root: Root, config: &Config) -> cmd::Result {
+        let status = Status::inspect(&root, self.path.as_ref())?;
+        if self.json || config.format() == Format::Json {
+            json::print(&status)?;
+        } else {
+            print!("{status}");
+        }
+        Ok(())
+    }
+}
+
+/// The state of the signing session in the root directory.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct Status {
+    stage: Stage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold: Option<u16>,
+    signers: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    participants: Vec<json::Identifier>,
+    signature: Signature,
+    shares: Vec<Share>,
+    next_steps: Vec<Step>,
+}
+
+/// The stage that the signing session is in.
+#[derive(Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+enum Stage {
+    /// No key has been generated yet.
+    Split,
+    /// Participants are generating nonces and commitments.
+    Commit,
+    /// Enough participants have committed for a signing package.
+    Prepare,
+    /// Participants are signing the signing package.
+    Sign,
+    /// All signature shares have been received.
+    Aggregate,
+    /// The signature has been aggregated.
+    Done,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Split => "waiting for key generation",
+            Self::Commit => "collecting commitments",
+            Self::Prepare => "ready to prepare the signing package",
+            Self::Sign => "collecting signature shares",
+            Self::Aggregate => "ready to aggregate",
+            Self::Done => "signature aggregated",
+        })
+    }
+}
+
+/// The state of the aggregated signature.
+#[derive(Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+enum Signature {
+    None,
+    Valid,
+    Invalid,
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Valid => "valid",
+            Self::Invalid => "INVALID",
+        })
+    }
+}
+
+/// The files of a participant in the root directory.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct Share {
+    share_index: usize,
+    identifier: json::Identifier,
+    /// The participant's nonces exist in their workspace.
+    nonces: bool,
+    /// The participant's commitments were received by the coordinator.
+    commitments: bool,
+    /// The participant is included in the signing package.
+    participant: bool,
+    /// The participant's signature share was received by the coordinator.
+    signature_share: bool,
+    /// The participant's commitments or signature share are still in their
+    /// own workspace and need to be sent to the coordinator.
+    unsent: bool,
+}
+
+/// The next step of a role.
+#[derive(Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+struct Step {
+    role: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    share_index: Option<usize>,
+    action: String,
+}
+
+impl Step {
+    fn new(role: Role, action: impl Into<String>) -> Self {
+        let (role, share_index) = match role {
+            Role::Dealer => ("dealer", None),
+            Role::Signer(index) => ("signer", Some(index)),
+            Role::Coordinator => ("coordinator", None),
+        };
+        Self {
+            role,
+            share_index,
+            action: action.into(),
+        }
+    }
+}
+
+impl Status {
+    fn inspect(root: &Root, path: Option<&derive::Path>) -> anyhow::Result<Self> {
+        let coordinator = root.workspace_unchecked(Role::Coordinator);
+        let Some(public_key) = [
+            coordinator.public_key(),
+            root.workspace_unchecked(Role::Dealer).public_key(),
+        ]
+        .into_iter()
+        .find(|path| path.exists()) else {
+            let mut status = Self {
+                stage: Stage::Split,
+                address: None,
+                threshold: None,
+                signers: 0,
+                message: None,
+                participants: Vec::new(),
+                signature: Signature::None,
+                shares: Vec::new(),
+                next_steps: Vec::new(),
+            };
+            status.next_steps = status.next_steps();
+            return Ok(status);
+        };
+        let mut package = frost::keys::PublicKeyPackage::deserialize(&fs::read(public_key)?)?;
+        if let Some(path) = path {
+            package = derive::public_key_package(&package, path);
+        }
+        let signers = package.verifying_shares().len();
+        let threshold = cmd::threshold(root)?;
+
+        let (signing, key) = if coordinator.signing_package().exists() {
+            let signing = SigningPackage::deserialize(&fs::read(coordinator.signing_package())?)?;
+            // Signatures of re-randomized signing sessions are for the
+            // randomized key.
+            let key = match signing.randomizer() {
+                Some(randomizer) => {
+                    rerandomize::randomized_key(package.verifying_key(), randomizer)
+                }
+                None => *package.verifying_key(),
+            };
+            (Some(signing.signing().clone()), key)
+        } else {
+            (None, *package.verifying_key())
+        };
+        let signature = if !coordinator.signature().exists() {
+            Signature::None
+        } else {
+            let signature = frost::Signature::deserialize(&fs::read(coordinator.signature())?)?;
+            let valid = signing
+                .as_ref()
+                .is_some_and(|signing| key.verify(signing.message(), &signature).is_ok());
+            if valid {
+                Signature::Valid
+            } else {
+                Signature::Invalid
+            }
+        };
+
+        let mut shares = Vec::new();
+        for index in 0..signers {
+            let identifier = cmd::identifier(index)?;
+            let signer = root.workspace_unchecked(Role::Signer(index));
+            let commitments = coordinator.commitments(index).exists();
+            let signature_share = coordinator.signature_share(index).exists();
+            shares.push(Share {
+                share_index: index,
+                identifier: json::Identifier(identifier),
+                nonces: signer.nonces(index).exists(),
+                commitments,
+                participant: signing
+                    .as_ref()
+                    .is_some_and(|signing| signing.signing_commitments().contains_key(&identifier)),
+                signature_share,
+                unsent: (!commitments && signer.commitments(index).exists())
+                    || (!signature_share && signer.signature_share(index).exists()),
+            });
+        }
+
+        let stage = match (&signing, signature) {
+            (_, Signature::Valid | Signature::Invalid) => Stage::Done,
+            (Some(_), Signature::None) => {
+                if shares
+                    .iter()
+                    .filter(|share| share.participant)
+                    .all(|share| share.signature_share)
+                {
+                    Stage::Aggregate
+                } else {
+                    Stage::Sign
+                }
+            }
+            (None, Signature::None) => {
+                let committed = shares.iter().filter(|share| share.commitments).count();
+                if threshold.is_some_and(|threshold| committed >= usize::from(threshold)) {
+                    Stage::Prepare
+                } else {
+                    Stage::Commit
+                }
+            }
+        };
+
+        let mut status = Self {
+            stage,
+            address: Some(Address::from_key(package.verifying_key())),
+            threshold,
+            signers,
+            message: signing
+                .as_ref()
+                .map(|signing| format!("{:#}", Hex(signing.message()))),
+            participants: signing
+                .as_ref()
+                .map(|signing| {
+                    signing
+                        .signing_commitments()
+                        .keys()
+                        .copied()
+                        .map(json::Identifier)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            signature,
+            shares,
+            next_steps: Vec::new(),
+        };
+        status.next_steps = status.next_steps();
+        Ok(status)
+    }
+
+    /// Determines the next step of each role that has something to do.
+    fn next_steps(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let send = |share: &Share, steps: &mut Vec<Step>| {
+            if share.unsent {
+                steps.push(Step::new(
+                    Role::Signer(share.share_index),
+                    "send your files to the coordinator",
+                ));
+            }
+        };
+        match self.stage {
+            Stage::Split => steps.push(Step::new(
+                Role::Dealer,
+                "generate the key shares with `safe-frost split`",
+            )),
+            Stage::Commit | Stage::Prepare => {
+                for share in &self.shares {
+                    if !share.nonces && !share.commitments {
+                        steps.push(Step::new(
+                            Role::Signer(share.share_index),
+                            format!(
+                                "generate nonces and commitments with \
+                                 `safe-frost commit --share-index {}`",
+                                share.share_index,
+                            ),
+                        ));
+                    }
+                    send(share, &mut steps);
+                }
+                let committed = self.shares.iter().filter(|share| share.commitments).count();
+                steps.push(Step::new(
+                    Role::Coordinator,
+                    match self.threshold {
+                        Some(threshold) if committed < usize::from(threshold) => {
+                            format!("wait for commitments, {committed} of {threshold} received",)
+                        }
+                        Some(_) => "prepare the signing package with `safe-frost prepare`".into(),
+                        None => format!(
+                            "prepare the signing package with `safe-frost prepare --threshold` \
+                             once enough participants have committed, {committed} received",
+                        ),
+                    },
+                ));
+            }
+            Stage::Sign => {
+                let mut waiting = 0;
+                for share in self.shares.iter().filter(|share| share.participant) {
+                    if share.signature_share {
+                        continue;
+                    }
+                    waiting += 1;
+                    if share.unsent {
+                        send(share, &mut steps);
+                    } else if share.nonces {
+                        steps.push(Step::new(
+                            Role::Signer(share.share_index),
+                            format!(
+                                "sign the signing package with `safe-frost sign --share-index {}`",
+                                share.share_index,
+                            ),
+                        ));
+                    } else {
+                        steps.push(Step::new(
+                            Role::Signer(share.share_index),
+                            "nonces are missing, a new signing package is needed",
+                        ));
+                    }
+                }
+                steps.push(Step::new(
+                    Role::Coordinator,
+                    format!("wait for {waiting} more signature shares"),
+                ));
+            }
+            Stage::Aggregate => steps.push(Step::new(
+                Role::Coordinator,
+                "aggregate the signature shares with `safe-frost aggregate`",
+            )),
+            Stage::Done => {
+                if self.signature == Signature::Invalid {
+                    steps.push(Step::new(
+                        Role::Coordinator,
+                        "the signature does not verify for the signing package, start a new \
+                         signing session",
+                    ));
+                }
+            }
+        }
+        steps
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "stage:        {}", self.stage)?;
+        if let Some(address) = &self.address {
+            writeln!(f, "address:      {address}")?;
+            match self.threshold {
+                Some(threshold) => writeln!(f, "threshold:    {threshold} of {}", self.signers)?,
+                None => writeln!(f, "threshold:    unknown of {}", self.signers)?,
+            }
+        }
+        if let Some(message) = &self.message {
+            writeln!(f, "message:      {message}")?;
+            write!(f, "participants:")?;
+            for identifier in &self.participants {
+                write!(f, " {}", Identifier(&identifier.0))?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "signature:    {}", self.signature)?;
+        if !self.shares.is_empty() {
+            writeln!(f, "shares:")?;
+        }
+        for share in &self.shares {
+            let files = [
+                (share.nonces, "nonces"),
+                (share.commitments, "commitments"),
+                (share.participant, "participant"),
+                (share.signature_share, "signature share"),
+                (share.unsent, "unsent files"),
+            ]
+            .into_iter()
+            .filter_map(|(present, file)| present.then_some(file))
+            .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "  {}: {}",
+                share.share_index,
+                if files.is_empty() {
+                    "-".to_owned()
+                } else {
+                    files.join(", ")
+                },
+            )?;
+        }
+        if !self.next_steps.is_empty() {
+            writeln!(f, "next steps:")?;
+        }
+        for step in &self.next_steps {
+            match step.share_index {
+                Some(index) => writeln!(f, "  {} {index}: {}", step.role, step.action)?,
+                None => writeln!(f, "  {}: {}", step.role, step.action)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,27 +1,427 @@
-use crate::cmd::{self, Root};
+use crate::{
+    address::Address,
+    cmd::{self, Input, Message, Role, Root, audit, groups, json},
+    data::{round1::SigningPackage, round2::SignatureSharePackage},
+    derive, evm,
+    fmt::{Coord, Hex},
+    hex,
+    rerandomize::{self, RandomizedParams},
+    schnorr,
+};
 use argh::FromArgs;
-use std::fs;
+use frost::serde::Deserialize;
+use std::str;
+use zeroize::Zeroizing;
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "verify")]
 /// verify a FROST signature
-pub struct Command {}
+pub struct Command {
+    #[argh(subcommand)]
+    subcommand: Option<Subcommand>,
+
+    /// the signature to verify, or `-` for standard input; defaults to the
+    /// signature in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// the signing package, or `-` for standard input; defaults to the signing
+    /// package in the root directory
+    #[argh(option)]
+    signing_package: Option<Input>,
+
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// the non-hardened derivation path of the child key, such as `m/0/1`;
+    /// defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+
+    /// verify a standalone signature instead of the signature in the root
+    /// directory, either as `R,z`, a serialized FROST signature, or the ABI
+    /// encoding of `info --abi-encode signature`
+    #[argh(option, from_str_fn(parse_signature))]
+    signature: Option<Box<schnorr::Signature>>,
+
+    /// the message of the standalone signature as a hexadecimal string
+    #[argh(option, short = 'm')]
+    message: Option<Message>,
+
+    /// the public key of the standalone signature, either as `{x,y}`
+    /// coordinates, SEC1 encoded, the ABI encoding of `info --abi-encode
+    /// public-key`, or an address when the signature includes the public key
+    #[argh(option, from_str_fn(parse_public_key))]
+    public_key: Option<schnorr::PublicKey>,
+
+    /// verify a batch of standalone signatures, or `-` for standard input, with
+    /// one record per line: either a JSON object with `publicKey`, `message`
+    /// and `signature` fields in the same formats as the options above, or the
+    /// hexadecimal ABI encoding of `(message, px, py, rx, ry, z)`
+    #[argh(option)]
+    batch: Option<Input>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    Share(Share),
+    Key(Key),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "share")]
+/// verify a single round-2 signature share
+struct Share {
+    /// share index
+    #[argh(option, short = 'i')]
+    share_index: usize,
+
+    /// the signature share to verify, or `-` for standard input; defaults to
+    /// the signature share in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// the signing package, or `-` for standard input; defaults to the signing
+    /// package in the root directory
+    #[argh(option)]
+    signing_package: Option<Input>,
+
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+
+    /// the non-hardened derivation path of the child key, such as `m/0/1`;
+    /// defaults to the group key itself
+    #[argh(option)]
+    path: Option<derive::Path>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "key")]
+/// verify a key share against the public key package
+struct Key {
+    /// share index
+    #[argh(option, short = 'i')]
+    share_index: usize,
+
+    /// the key share to verify, or `-` for standard input; defaults to the key
+    /// share in the root directory
+    #[argh(option, long = "in")]
+    input: Option<Input>,
+
+    /// a secret share from a dealer to verify instead of a key share, or `-`
+    /// for standard input; it is verified against its verifiable secret
+    /// sharing commitment before being converted into a key share
+    #[argh(option)]
+    secret_share: Option<Input>,
+
+    /// the public key package, or `-` for standard input; defaults to the
+    /// public key package in the root directory
+    #[argh(option)]
+    public_key_package: Option<Input>,
+}
 
 impl Command {
     pub fn run(self, root: Root) -> cmd::Result {
-        let data = fs::read(root.public_key())?;
-        let pubkey = frost::keys::PublicKeyPackage::deserialize(&data)?;
+        match self.subcommand {
+            Some(Subcommand::Share(cmd)) => return cmd.run(root.workspace(Role::Coordinator)?),
+            Some(Subcommand::Key(cmd)) => return cmd.run(root),
+            None => {}
+        }
+        let root_inputs = self.input.is_some()
+            || self.signing_package.is_some()
+            || self.public_key_package.is_some()
+            || self.path.is_some();
+        if let Some(batch) = self.batch {
+            if root_inputs
+                || self.signature.is_some()
+                || self.message.is_some()
+                || self.public_key.is_some()
+            {
+                anyhow::bail!("--batch cannot be combined with other options");
+            }
+            return verify_batch(&batch.read()?);
+        }
+        if let Some(signature) = self.signature {
+            if root_inputs {
+                anyhow::bail!(
+                    "--signature cannot be combined with --in, --signing-package, \
+                     --public-key-package or --path"
+                );
+            }
+            let message = self
+                .message
+                .ok_or_else(|| anyhow::anyhow!("--signature requires a --message"))?;
+            return standalone(&signature, &message, self.public_key.as_ref());
+        }
+        if self.message.is_some() || self.public_key.is_some() {
+            anyhow::bail!("--message and --public-key require a --signature");
+        }
+
+        let root = root.workspace(Role::Coordinator)?;
 
-        let data = fs::read(root.signing_package())?;
-        let signing = frost::SigningPackage::deserialize(&data)?;
+        let input = self
+            .public_key_package
+            .unwrap_or_else(|| root.public_key().into());
+        let mut pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+        if let Some(path) = &self.path {
+            pubkey = derive::public_key_package(&pubkey, path);
+        }
 
-        let data = fs::read(root.signature())?;
+        let input = self
+            .signing_package
+            .unwrap_or_else(|| root.signing_package().into());
+        let package = SigningPackage::deserialize(&input.read()?)?;
+        let signing = package.signing();
+        let key = match package.randomizer() {
+            Some(randomizer) => rerandomize::randomized_key(pubkey.verifying_key(), randomizer),
+            None => *pubkey.verifying_key(),
+        };
+
+        let input = self.input.unwrap_or_else(|| root.signature().into());
+        let data = input.read()?;
         let signature = frost::Signature::deserialize(&data)?;
 
-        pubkey
-            .verifying_key()
-            .verify(signing.message(), &signature)?;
+        let result = key.verify(signing.message(), &signature);
+        audit::record(
+            &root,
+            &audit::Event::Verify {
+                address: Address::from_key(&key),
+                message: format!("{:#}", Hex(signing.message())),
+                signature: json::fingerprint(&data),
+                valid: result.is_ok(),
+            },
+        )?;
+        result?;
+
+        Ok(())
+    }
+}
+
+impl Share {
+    fn run(self, root: Root) -> cmd::Result {
+        let identifier = cmd::identifier(self.share_index)?;
+
+        let input = self
+            .public_key_package
+            .unwrap_or_else(|| root.public_key().into());
+        let mut pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+        if let Some(path) = &self.path {
+            pubkey = derive::public_key_package(&pubkey, path);
+        }
+
+        let input = self
+            .signing_package
+            .unwrap_or_else(|| root.signing_package().into());
+        let package = SigningPackage::deserialize(&input.read()?)?;
+        let signing = package.signing();
+        let Some(verifying_share) = pubkey.verifying_shares().get(&identifier) else {
+            anyhow::bail!("public key package has no share {}", self.share_index);
+        };
+        let (verifying_share, key) = match package.randomizer() {
+            Some(randomizer) => {
+                let params = RandomizedParams::from_randomizer(pubkey.verifying_key(), randomizer);
+                (
+                    rerandomize::verifying_share(verifying_share, &params),
+                    *params.randomized_verifying_key(),
+                )
+            }
+            None => (*verifying_share, *pubkey.verifying_key()),
+        };
+
+        // The input may hold multiple concatenated signature shares, such as
+        // when streamed over standard input, so look for the one to verify.
+        let input = self
+            .input
+            .unwrap_or_else(|| root.signature_share(self.share_index).into());
+        let Some(share) = SignatureSharePackage::deserialize_all(&input.read()?)?
+            .into_iter()
+            .find(|share| share.identifier() == &identifier)
+        else {
+            anyhow::bail!("no signature share for share {}", self.share_index);
+        };
+
+        let result = frost_core::verify_signature_share(
+            identifier,
+            &verifying_share,
+            share.signature(),
+            signing,
+            &key,
+        );
+        audit::record(
+            &root,
+            &audit::Event::VerifyShare {
+                participant: json::Identifier(identifier),
+                message: format!("{:#}", Hex(signing.message())),
+                valid: result.is_ok(),
+            },
+        )?;
+        result.map_err(|e| anyhow::anyhow!("invalid signature share {}: {e}", self.share_index))?;
+
+        Ok(())
+    }
+}
+
+impl Key {
+    fn run(self, root: Root) -> cmd::Result {
+        let threshold = match &root.group {
+            Some(group) => groups::threshold(&root, group)?,
+            None => None,
+        };
+        let root = root.workspace(Role::Signer(self.share_index))?;
+        let identifier = cmd::identifier(self.share_index)?;
+
+        let key = match self.secret_share {
+            Some(input) => {
+                let share = frost::keys::SecretShare::deserialize(&input.read_secret()?)
+                    .map(Zeroizing::new)?;
+                share.verify().map_err(|e| {
+                    anyhow::anyhow!("secret share does not match its commitment: {e}")
+                })?;
+                frost::keys::KeyPackage::try_from((*share).clone()).map(Zeroizing::new)?
+            }
+            None => {
+                let data = match self.input {
+                    Some(input) => input.read_secret()?,
+                    None => cmd::read_secret(&root.signing_key(self.share_index))?,
+                };
+                frost::keys::KeyPackage::deserialize(&data).map(Zeroizing::new)?
+            }
+        };
+
+        let input = self
+            .public_key_package
+            .unwrap_or_else(|| root.public_key().into());
+        let pubkey = frost::keys::PublicKeyPackage::deserialize(&input.read()?)?;
+
+        if key.identifier() != &identifier {
+            anyhow::bail!("key share is not for share {}", self.share_index);
+        }
+        if key.verifying_share() != &frost::keys::VerifyingShare::from(*key.signing_share()) {
+            anyhow::bail!("key share verifying share does not match its signing share");
+        }
+        if pubkey.verifying_shares().get(&identifier) != Some(key.verifying_share()) {
+            anyhow::bail!("key share verifying share does not match the public key package");
+        }
+        if key.verifying_key() != pubkey.verifying_key() {
+            anyhow::bail!("key share group public key does not match the public key package");
+        }
+
+        let min_signers = usize::from(*key.min_signers());
+        if min_signers < 2 || min_signers > pubkey.verifying_shares().len() {
+            anyhow::bail!(
+                "key share threshold {min_signers} is invalid for {} signers",
+                pubkey.verifying_shares().len(),
+            );
+        }
+        if let Some(threshold) = threshold
+            && *key.min_signers() != threshold
+        {
+            anyhow::bail!(
+                "key share threshold {min_signers} does not match the group threshold {threshold}",
+            );
+        }
 
         Ok(())
     }
 }
+
+/// Verifies a standalone signature, reporting its public key and whether or
+/// not it is supported by the EVM verifier.
+fn standalone(
+    signature: &schnorr::Signature,
+    message: &Message,
+    public_key: Option<&schnorr::PublicKey>,
+) -> cmd::Result {
+    let point = signature.signer(public_key)?;
+
+    let key = point.to_key()?;
+    println!("address:          {}", Address::from_key(&key));
+    println!("public key:       {}", Coord(&key.to_element()));
+    println!("valid public key: {}", evm::is_valid_public_key(&point));
+
+    key.verify(message.as_ref(), &signature.to_frost()?)?;
+    Ok(())
+}
+
+/// Verifies a batch of standalone signature records, reporting the lines of
+/// the invalid ones.
+fn verify_batch(data: &[u8]) -> cmd::Result {
+    let mut lines = Vec::new();
+    let mut entries = Vec::new();
+    for (number, line) in str::from_utf8(data)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let entry = parse_record(line).map_err(|e| anyhow::anyhow!("line {}: {e}", number + 1))?;
+        lines.push(number + 1);
+        entries.push(entry);
+    }
+
+    let invalid = schnorr::verify_batch(&entries);
+    for index in &invalid {
+        println!("line {}: invalid signature", lines[*index]);
+    }
+    if !invalid.is_empty() {
+        anyhow::bail!(
+            "{} of {} signatures are invalid",
+            invalid.len(),
+            entries.len()
+        );
+    }
+    Ok(())
+}
+
+/// A JSON standalone signature record.
+#[derive(Deserialize)]
+#[serde(
+    crate = "::frost::serde",
+    rename_all = "camelCase",
+    deny_unknown_fields
+)]
+struct Record {
+    public_key: Option<String>,
+    message: String,
+    signature: String,
+}
+
+fn parse_record(line: &str) -> anyhow::Result<(frost::VerifyingKey, Vec<u8>, frost::Signature)> {
+    let (public_key, message, signature) = if line.starts_with('{') {
+        let record = serde_json::from_str::<Record>(line)?;
+        let public_key = record
+            .public_key
+            .as_deref()
+            .map(str::parse::<schnorr::PublicKey>)
+            .transpose()?;
+        let message = hex::decode(&record.message)?;
+        (public_key, message, record.signature.parse()?)
+    } else {
+        let bytes = hex::decode::<Vec<u8>>(line)?;
+        if bytes.len() != 192 {
+            anyhow::bail!("ABI encoded record must be 192 bytes");
+        }
+        let message = bytes[..32].to_vec();
+        (None, message, schnorr::Signature::from_bytes(&bytes[32..])?)
+    };
+
+    let key = signature.signer(public_key.as_ref())?.to_key()?;
+    Ok((key, message, signature.to_frost()?))
+}
+
+fn parse_signature(value: &str) -> Result<Box<schnorr::Signature>, String> {
+    value
+        .parse()
+        .map(Box::new)
+        .map_err(|e| format!("invalid signature: {e}"))
+}
+
+fn parse_public_key(value: &str) -> Result<schnorr::PublicKey, String> {
+    value
+        .parse()
+        .map_err(|e| format!("invalid public key: {e}"))
+}
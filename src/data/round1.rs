@@ -1,3 +1,4 @@
+use crate::rerandomize::Randomizer;
 use frost::serde::{Deserialize, Serialize};
 
 /// A round-1 commitment package.
@@ -38,11 +39,72 @@ impl CommitmentsPackage {
         postcard::to_allocvec(self)
     }
 
-    /// Deserialize a commitment package from a byte slice.
+    /// Deserialize all commitment packages from a byte slice of zero or more
+    /// concatenated packages, such as multiple files piped over standard input.
     ///
     /// This uses the [`postcard`] serialization format, to match the default
     /// format used by the [`frost`] crate.
-    pub fn deserialize(data: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(data)
+    pub fn deserialize_all(mut data: &[u8]) -> Result<Vec<Self>, postcard::Error> {
+        let mut result = Vec::new();
+        while !data.is_empty() {
+            let (package, rest) = postcard::take_from_bytes(data)?;
+            result.push(package);
+            data = rest;
+        }
+        Ok(result)
+    }
+}
+
+/// A round-1 signing package, with the randomizer of a re-randomized signing
+/// session.
+///
+/// It is serialized as the [`frost::SigningPackage`] followed by the
+/// serialized randomizer, if any, so signing packages of regular signing
+/// sessions are unchanged.
+pub struct SigningPackage {
+    signing: frost::SigningPackage,
+    randomizer: Option<Randomizer>,
+}
+
+impl SigningPackage {
+    /// Creates a new signing package.
+    pub fn new(signing: frost::SigningPackage, randomizer: Option<Randomizer>) -> Self {
+        Self {
+            signing,
+            randomizer,
+        }
+    }
+
+    /// Gets the FROST signing package.
+    pub fn signing(&self) -> &frost::SigningPackage {
+        &self.signing
+    }
+
+    /// Gets the randomizer of a re-randomized signing session.
+    pub fn randomizer(&self) -> Option<Randomizer> {
+        self.randomizer
+    }
+
+    /// Serialize the signing package into a byte vector.
+    pub fn serialize(&self) -> Result<Vec<u8>, frost::Error> {
+        let mut data = self.signing.serialize()?;
+        if let Some(randomizer) = &self.randomizer {
+            data.extend_from_slice(&randomizer.serialize());
+        }
+        Ok(data)
+    }
+
+    /// Deserialize a signing package from a byte slice.
+    pub fn deserialize(data: &[u8]) -> Result<Self, frost::Error> {
+        let (signing, rest) = postcard::take_from_bytes::<frost::SigningPackage>(data)
+            .map_err(|_| frost::Error::DeserializationError)?;
+        let randomizer = match rest {
+            [] => None,
+            rest => Some(Randomizer::deserialize(rest)?),
+        };
+        Ok(Self {
+            signing,
+            randomizer,
+        })
     }
 }
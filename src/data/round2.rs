@@ -35,11 +35,18 @@ impl SignatureSharePackage {
         postcard::to_allocvec(self)
     }
 
-    /// Deserialize a signature share package from a byte slice.
+    /// Deserialize all signature share packages from a byte slice of zero or more
+    /// concatenated packages, such as multiple files piped over standard input.
     ///
     /// This uses the [`postcard`] serialization format, to match the default
     /// format used by the [`frost`] crate.
-    pub fn deserialize(data: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(data)
+    pub fn deserialize_all(mut data: &[u8]) -> Result<Vec<Self>, postcard::Error> {
+        let mut result = Vec::new();
+        while !data.is_empty() {
+            let (package, rest) = postcard::take_from_bytes(data)?;
+            result.push(package);
+            data = rest;
+        }
+        Ok(result)
     }
 }
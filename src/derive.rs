@@ -0,0 +1,121 @@
+//! Non-hardened derivation of child keys from a FROST group key.
+//!
+//! Child keys are derived by additively tweaking the group key, similar to
+//! BIP-32 non-hardened derivation: each path index `i` derives a tweak
+//! `t = keccak256("safe-frost/derive" || P || i) mod n` from the compressed
+//! parent public key `P`, and the child public key is `P + t⋅G`. Since the
+//! Lagrange coefficients of any signing set sum to one, adding the same tweak
+//! to every signing share yields shares of the child secret key, so a single
+//! distributed key generation can be used for any number of accounts.
+
+use crate::{bip32, keccak};
+use frost::{
+    VerifyingKey,
+    keys::{KeyPackage, PublicKeyPackage, SigningShare, VerifyingShare},
+};
+use k256::{
+    ProjectivePoint, Scalar, U256,
+    elliptic_curve::{group::GroupEncoding as _, ops::Reduce},
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Domain separation tag for the derivation tweaks.
+const DOMAIN: &[u8] = b"safe-frost/derive";
+
+/// A non-hardened derivation path, such as `m/0/1`.
+///
+/// Paths are parsed as BIP-32 paths, but hardened indices are rejected as
+/// hardened derivation would require the group secret key.
+#[derive(Clone, Debug)]
+pub struct Path(bip32::Path);
+
+impl Path {
+    /// Returns the total additive tweak of the path for a group public key.
+    fn tweak(&self, key: &VerifyingKey) -> Scalar {
+        let mut point = key.to_element();
+        let mut tweak = Scalar::ZERO;
+        for index in self.0.indices() {
+            let mut preimage = DOMAIN.to_vec();
+            preimage.extend_from_slice(&point.to_bytes());
+            preimage.extend_from_slice(&index.to_be_bytes());
+            let step = <Scalar as Reduce<U256>>::reduce_bytes(&keccak::v256(&preimage).into());
+            tweak += step;
+            point += ProjectivePoint::GENERATOR * step;
+        }
+        tweak
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Path {
+    type Err = InvalidPath;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = s.parse::<bip32::Path>().map_err(|_| InvalidPath::Format)?;
+        if path.is_hardened() {
+            return Err(InvalidPath::Hardened);
+        }
+        Ok(Self(path))
+    }
+}
+
+/// Derives the public key package of a child key.
+pub fn public_key_package(key: &PublicKeyPackage, path: &Path) -> PublicKeyPackage {
+    let tweak = path.tweak(key.verifying_key());
+    let shares = key
+        .verifying_shares()
+        .iter()
+        .map(|(identifier, share)| (*identifier, tweak_share(share, &tweak)))
+        .collect();
+    PublicKeyPackage::new(shares, tweak_key(key.verifying_key(), &tweak))
+}
+
+/// Derives the key package of a child key for a signer.
+pub fn key_package(key: &KeyPackage, path: &Path) -> KeyPackage {
+    let tweak = path.tweak(key.verifying_key());
+    KeyPackage::new(
+        *key.identifier(),
+        SigningShare::new(key.signing_share().to_scalar() + tweak),
+        tweak_share(key.verifying_share(), &tweak),
+        tweak_key(key.verifying_key(), &tweak),
+        *key.min_signers(),
+    )
+}
+
+fn tweak_share(share: &VerifyingShare, tweak: &Scalar) -> VerifyingShare {
+    VerifyingShare::new(share.to_element() + ProjectivePoint::GENERATOR * tweak)
+}
+
+fn tweak_key(key: &VerifyingKey, tweak: &Scalar) -> VerifyingKey {
+    VerifyingKey::new(key.to_element() + ProjectivePoint::GENERATOR * tweak)
+}
+
+/// An error parsing a derivation path.
+#[derive(Debug)]
+pub enum InvalidPath {
+    /// The path is not of the form `m/i/j/...`.
+    Format,
+    /// The path has hardened indices, which are not supported.
+    Hardened,
+}
+
+impl Display for InvalidPath {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Format => f.write_str("invalid derivation path, expected `m/i/j/...`"),
+            Self::Hardened => f.write_str(
+                "hardened derivation path, only non-hardened paths can be derived from a group key",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidPath {}
@@ -1,3 +1,4 @@
+use crate::schnorr::Point;
 use frost::{Field as _, Secp256K1ScalarField, VerifyingKey, keys::PublicKeyPackage};
 use k256::elliptic_curve::sec1::ToEncodedPoint as _;
 use std::fmt::{self, Display, Formatter};
@@ -18,12 +19,24 @@ use std::fmt::{self, Display, Formatter};
 ///    encode when the signature `R` point encoded in the `r` value has an
 ///    x-coordinate of `R.x = r + n` (where `n` is the order of the curve).
 pub fn verified_public_key(key: &PublicKeyPackage) -> Result<&VerifyingKey, NotSupported> {
-    let key = key.verifying_key();
+    verified_key(key.verifying_key())
+}
+
+/// Verifies whether or not a `secp256k1` verifying key is supported with the
+/// EVM verifier implementation, see [`verified_public_key`].
+pub fn verified_key(key: &VerifyingKey) -> Result<&VerifyingKey, NotSupported> {
     let point = key.to_element().to_encoded_point(true);
     let _ = Secp256K1ScalarField::deserialize(point.as_bytes()[1..].try_into()?)?;
     Ok(key)
 }
 
+/// Returns whether or not the affine coordinates of a public key are accepted by
+/// `FROST.isValidPublicKey`, that is, they are a point on the curve with an
+/// x-coordinate that is smaller than the curve order.
+pub fn is_valid_public_key(point: &Point) -> bool {
+    point.to_affine().is_some() && Secp256K1ScalarField::deserialize(&point.x).is_ok()
+}
+
 /// An error decoding a hex string.
 #[derive(Debug)]
 pub struct NotSupported;
@@ -1,3 +1,4 @@
+use crate::tx::Uint;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use std::fmt::{self, Display, Formatter};
 
@@ -39,3 +40,13 @@ where
         write!(f, "{{0x{x:032x},0x{y:032x}}}")
     }
 }
+
+/// Format a FROST identifier as a decimal integer.
+pub struct Identifier<'a>(pub &'a frost::Identifier);
+
+impl Display for Identifier<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let bytes = self.0.serialize().try_into().unwrap();
+        Uint(bytes).fmt(f)
+    }
+}
@@ -6,7 +6,7 @@ where
     T: BytesOfLength,
 {
     let hex = value.strip_prefix("0x").unwrap_or(value);
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return Err(DecodeError::OddLength);
     }
     let nibble = |b: u8| match b {
@@ -0,0 +1,262 @@
+//! Ethereum Web3 Secret Storage (V3 keystore) encryption of secret keys.
+
+use crate::{address::Address, fmt::Hex, hex, keccak};
+use ctr::cipher::{KeyIvInit as _, StreamCipher as _};
+use frost::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use rand::{CryptoRng, RngCore};
+use std::fmt::{self, Display, Formatter};
+use subtle::ConstantTimeEq as _;
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// The scrypt parameters used for encrypting new keystores, matching the
+/// "standard" parameters of `geth`.
+const SCRYPT_LOG_N: u8 = 18;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// The largest PBKDF2 iteration count that keystores are decrypted with.
+///
+/// Key derivation parameters come from the keystore file, so they are capped
+/// to keep a crafted keystore from hanging or exhausting memory. The scrypt
+/// parameters are capped at the ones used for encrypting new keystores.
+const PBKDF2_MAX_C: u32 = 1 << 20;
+
+/// The derived key length, which is always 32 bytes for V3 keystores.
+const DKLEN: usize = 32;
+
+/// A V3 keystore file.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde")]
+struct Keystore {
+    version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<Bytes>,
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde")]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: Bytes,
+    #[serde(flatten)]
+    kdf: Kdf,
+    mac: Bytes,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde")]
+struct CipherParams {
+    iv: Bytes,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(
+    crate = "::frost::serde",
+    tag = "kdf",
+    content = "kdfparams",
+    rename_all = "lowercase"
+)]
+enum Kdf {
+    Scrypt {
+        dklen: usize,
+        n: u64,
+        r: u32,
+        p: u32,
+        salt: Bytes,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        salt: Bytes,
+    },
+}
+
+impl Kdf {
+    /// Derives the encryption key from a password.
+    fn derive(&self, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+        match self {
+            Self::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                if !n.is_power_of_two()
+                    || *n > 1 << SCRYPT_LOG_N
+                    || *r > SCRYPT_R
+                    || *p > SCRYPT_P
+                    || *dklen != DKLEN
+                {
+                    return Err(Error::Unsupported);
+                }
+                let params = scrypt::Params::new(n.ilog2() as _, *r, *p, *dklen)
+                    .map_err(|_| Error::Unsupported)?;
+                let mut key = Zeroizing::new(vec![0; *dklen]);
+                scrypt::scrypt(password, &salt.0, &params, &mut key)
+                    .map_err(|_| Error::Unsupported)?;
+                Ok(key)
+            }
+            Self::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                if prf != "hmac-sha256" || *c == 0 || *c > PBKDF2_MAX_C || *dklen != DKLEN {
+                    return Err(Error::Unsupported);
+                }
+                let mut key = Zeroizing::new(vec![0; *dklen]);
+                pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, &salt.0, *c, &mut key);
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// Decrypts the secret key of a keystore, returning it along with the address
+/// recorded in the keystore, if any.
+pub fn decrypt(
+    data: &[u8],
+    password: &[u8],
+) -> Result<(Zeroizing<Vec<u8>>, Option<Address>), Error> {
+    let keystore = serde_json::from_slice::<Keystore>(data).map_err(Error::Json)?;
+    let crypto = &keystore.crypto;
+    if keystore.version != 3 || crypto.cipher != "aes-128-ctr" {
+        return Err(Error::Unsupported);
+    }
+    let address = keystore
+        .address
+        .map(|address| address.0.try_into().map(Address::from_bytes))
+        .transpose()
+        .map_err(|_| Error::Unsupported)?;
+
+    let key = crypto.kdf.derive(password)?;
+    if !bool::from(mac(&key, &crypto.ciphertext.0).ct_eq(crypto.mac.0.as_slice())) {
+        return Err(Error::Password);
+    }
+    let iv = <[u8; 16]>::try_from(crypto.cipherparams.iv.0.as_slice())
+        .map_err(|_| Error::Unsupported)?;
+    let mut secret = Zeroizing::new(crypto.ciphertext.0.clone());
+    Aes128Ctr::new(key[..16].into(), &iv.into()).apply_keystream(&mut secret);
+    Ok((secret, address))
+}
+
+/// Encrypts a secret key into a JSON keystore for the specified address.
+pub fn encrypt<R>(
+    secret: &[u8],
+    address: Address,
+    password: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, Error>
+where
+    R: CryptoRng + RngCore,
+{
+    let mut salt = [0; 32];
+    let mut iv = [0; 16];
+    let mut id = [0; 16];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut iv);
+    rng.fill_bytes(&mut id);
+
+    let kdf = Kdf::Scrypt {
+        dklen: DKLEN,
+        n: 1 << SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: Bytes(salt.to_vec()),
+    };
+    let key = kdf.derive(password)?;
+    let mut ciphertext = secret.to_vec();
+    Aes128Ctr::new(key[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    let keystore = Keystore {
+        version: 3,
+        id: Some(uuid(id)),
+        address: Some(Bytes(address.as_slice().to_vec())),
+        crypto: Crypto {
+            cipher: "aes-128-ctr".to_owned(),
+            cipherparams: CipherParams {
+                iv: Bytes(iv.to_vec()),
+            },
+            mac: Bytes(mac(&key, &ciphertext).to_vec()),
+            ciphertext: Bytes(ciphertext),
+            kdf,
+        },
+    };
+    serde_json::to_vec_pretty(&keystore).map_err(Error::Json)
+}
+
+/// Computes the keystore MAC of a ciphertext.
+fn mac(key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    keccak::v256(&[&key[16..32], ciphertext].concat())
+}
+
+/// Formats random bytes as a version 4 UUID.
+fn uuid(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    format!(
+        "{}-{}-{}-{}-{}",
+        Hex(&bytes[..4]),
+        Hex(&bytes[4..6]),
+        Hex(&bytes[6..8]),
+        Hex(&bytes[8..10]),
+        Hex(&bytes[10..]),
+    )
+}
+
+/// Keystore bytes, hex encoded without a `0x` prefix.
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&Hex(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(&s)
+            .map(Self)
+            .map_err(|e| de::Error::custom(format!("invalid bytes: {e}")))
+    }
+}
+
+/// An error decrypting or encrypting a keystore.
+#[derive(Debug)]
+pub enum Error {
+    /// The keystore is not valid JSON.
+    Json(serde_json::Error),
+    /// The keystore uses an unsupported version, cipher or key derivation.
+    Unsupported,
+    /// The keystore MAC does not match, meaning the password is wrong.
+    Password,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid keystore: {err}"),
+            Self::Unsupported => f.write_str("unsupported keystore parameters"),
+            Self::Password => f.write_str("incorrect keystore password"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,12 +1,20 @@
 //! Sample Frost threshold signature generation.
 
+mod abi;
 mod address;
+mod bip32;
 mod cmd;
 mod data;
+mod derive;
 mod evm;
 mod fmt;
 mod hex;
 mod keccak;
+mod keystore;
+mod paper;
+mod rerandomize;
+mod schnorr;
+mod tx;
 
 use argh::FromArgs;
 
@@ -16,15 +24,44 @@ struct Args {
     #[argh(subcommand)]
     subcommand: cmd::Subcommand,
 
-    /// the FROST root directory
-    #[argh(option, short = 'R', default = "cmd::Root::default()")]
-    root_directory: cmd::Root,
+    /// the FROST root directory; defaults to `.frost`
+    #[argh(option, short = 'R')]
+    root_directory: Option<cmd::Root>,
+
+    /// the layout of the FROST root directory: `flat` (default) keeps all
+    /// files in the root directory, `roles` keeps separate `dealer/`,
+    /// `signer-${index}/` and `coordinator/` workspaces
+    #[argh(option, short = 'L')]
+    layout: Option<cmd::Layout>,
+
+    /// the named FROST group in the root directory to use, by default the root
+    /// directory itself holds a single group
+    #[argh(option, short = 'G', from_str_fn(cmd::groups::parse_name))]
+    group: Option<String>,
+
+    /// the output format of commands that display information: `text`
+    /// (default) or `json`
+    #[argh(option, short = 'F')]
+    format: Option<cmd::Format>,
 }
 
 fn main() {
     let args = argh::from_env::<Args>();
-    if let Err(err) = args.subcommand.run(args.root_directory) {
+    if let Err(err) = run(args) {
         eprintln!("ERROR: {err}");
         std::process::exit(1);
     }
 }
+
+fn run(args: Args) -> cmd::Result {
+    // Options on the command line take precedence over the configuration.
+    let mut config = cmd::config::Config::load(args.root_directory.as_ref())?;
+    config.format = args.format.or(config.format);
+    let root = args
+        .root_directory
+        .or_else(|| config.root_directory.clone().map(cmd::Root::from))
+        .unwrap_or_default()
+        .with_layout(args.layout.or(config.layout).unwrap_or_default())
+        .with_group(args.group.or_else(|| config.group.clone()));
+    args.subcommand.run(root, &config)
+}
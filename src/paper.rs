@@ -0,0 +1,188 @@
+//! Human-transcribable paper backups of key shares.
+//!
+//! A paper backup holds the signing share, the group public key, the share
+//! index and the threshold as lines of grouped hexadecimal digits. Each line
+//! has a checksum over its number and its digits, so that transcription errors
+//! and swapped lines are detected and located, and the backup ends with a
+//! checksum over the entire share.
+
+use crate::{address::Address, fmt::Hex, hex, keccak};
+use std::fmt::{self, Display, Formatter, Write as _};
+use zeroize::{Zeroize as _, Zeroizing};
+
+/// The title line of a paper backup.
+const TITLE: &str = "safe-frost key share";
+
+/// The number of bytes per line.
+const LINE: usize = 8;
+
+/// The number of lines of a paper backup.
+const LINES: usize = 9;
+
+/// A key share, as recorded in a paper backup.
+pub struct Share {
+    pub index: u16,
+    pub threshold: u16,
+    pub signing_share: frost::keys::SigningShare,
+    pub verifying_key: frost::VerifyingKey,
+}
+
+impl Share {
+    /// Renders the share as a paper backup.
+    pub fn encode(&self) -> Result<Zeroizing<String>, Error> {
+        let payload = self.payload()?;
+        let mut paper = Zeroizing::new(String::new());
+        let mut line = |s: &str| {
+            paper.push_str(s);
+            paper.push('\n');
+        };
+        line(TITLE);
+        line(&format!("share index: {}", self.index));
+        line(&format!("threshold:   {}", self.threshold));
+        line(&format!("address:     {}", self.address()));
+        for (number, bytes) in payload.chunks(LINE).enumerate() {
+            let mut digits = Zeroizing::new(String::new());
+            for group in bytes.chunks(2) {
+                write!(digits, " {}", Hex(group)).unwrap();
+            }
+            line(&format!(
+                "{:>2} {}  {}",
+                number + 1,
+                *digits,
+                Hex(&line_checksum(number + 1, bytes)),
+            ));
+        }
+        line(&format!("checksum:    {}", Hex(&checksum(&payload))));
+        Ok(paper)
+    }
+
+    /// Reads a share from a paper backup, verifying all of its checksums.
+    pub fn decode(paper: &str) -> Result<Self, Error> {
+        let mut lines = paper.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some(TITLE) {
+            return Err(Error::Format);
+        }
+
+        let mut fields = Vec::new();
+        let mut payload = Zeroizing::new(Vec::with_capacity(LINE * LINES));
+        for line in lines {
+            if let Some((name, value)) = line.split_once(':') {
+                fields.push((name.trim(), value.trim()));
+                continue;
+            }
+
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            let [number, digits @ .., sum] = tokens.as_slice() else {
+                return Err(Error::Format);
+            };
+            let number = number.parse::<usize>().map_err(|_| Error::Format)?;
+            if number > LINES {
+                return Err(Error::Format);
+            }
+            if number != payload.len() / LINE + 1 {
+                return Err(Error::MissingLine(payload.len() / LINE + 1));
+            }
+            let digits = Zeroizing::new(digits.concat());
+            let bytes = hex::decode::<[u8; LINE]>(&digits)
+                .map(Zeroizing::new)
+                .map_err(|_| Error::Line(number))?;
+            if hex::decode::<[u8; 2]>(sum).ok() != Some(line_checksum(number, &*bytes)) {
+                return Err(Error::Line(number));
+            }
+            payload.extend_from_slice(&*bytes);
+        }
+        if payload.len() != LINE * LINES {
+            return Err(Error::MissingLine(payload.len() / LINE + 1));
+        }
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map(|(_, value)| *value)
+                .ok_or(Error::Format)
+        };
+        if hex::decode::<[u8; 4]>(field("checksum")?).ok() != Some(checksum(&payload)) {
+            return Err(Error::Checksum);
+        }
+
+        let share = Self {
+            signing_share: frost::keys::SigningShare::deserialize(&payload[..32])
+                .map_err(|_| Error::Checksum)?,
+            verifying_key: frost::VerifyingKey::deserialize(&payload[32..65])
+                .map_err(|_| Error::Checksum)?,
+            index: u16::from_be_bytes([payload[65], payload[66]]),
+            threshold: u16::from_be_bytes([payload[67], payload[68]]),
+        };
+        if payload[69..].iter().any(|&b| b != 0) {
+            return Err(Error::Checksum);
+        }
+        if field("share index")? != share.index.to_string()
+            || field("threshold")? != share.threshold.to_string()
+            || !field("address")?.eq_ignore_ascii_case(&share.address().to_string())
+        {
+            return Err(Error::Header);
+        }
+        Ok(share)
+    }
+
+    fn payload(&self) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let mut payload = Zeroizing::new(Vec::with_capacity(LINE * LINES));
+        payload.extend_from_slice(&self.signing_share.serialize());
+        payload.extend_from_slice(&self.verifying_key.serialize().map_err(|_| Error::Format)?);
+        payload.extend_from_slice(&self.index.to_be_bytes());
+        payload.extend_from_slice(&self.threshold.to_be_bytes());
+        payload.resize(LINE * LINES, 0);
+        Ok(payload)
+    }
+
+    fn address(&self) -> Address {
+        Address::from_key(&self.verifying_key)
+    }
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+fn line_checksum(number: usize, bytes: &[u8]) -> [u8; 2] {
+    let mut preimage = Zeroizing::new(vec![number as u8]);
+    preimage.extend_from_slice(bytes);
+    keccak::v256(&preimage)[..2].try_into().unwrap()
+}
+
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    keccak::v256(payload)[..4].try_into().unwrap()
+}
+
+/// An error reading a paper backup.
+#[derive(Debug)]
+pub enum Error {
+    /// The backup is not in the paper backup format.
+    Format,
+    /// A line is missing or out of order.
+    MissingLine(usize),
+    /// A line does not match its checksum.
+    Line(usize),
+    /// The share does not match the checksum of the entire backup.
+    Checksum,
+    /// The share index, threshold or address does not match the share.
+    Header,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Format => f.write_str("invalid paper backup format"),
+            Self::MissingLine(number) => write!(f, "line {number} is missing or out of order"),
+            Self::Line(number) => write!(f, "line {number} does not match its checksum"),
+            Self::Checksum => f.write_str("share does not match the backup checksum"),
+            Self::Header => {
+                f.write_str("share index, threshold or address does not match the share")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
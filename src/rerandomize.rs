@@ -0,0 +1,46 @@
+//! Re-randomized FROST signing.
+//!
+//! In a re-randomized signing session, the coordinator chooses a random scalar
+//! `α`, the randomizer, and the signature is produced for the randomized key
+//! `P + α⋅G` instead of the group key `P`. Participants add the randomizer to
+//! their signing shares, and the randomizer is bound to the signing package it
+//! was generated for. Without the randomizer, a signature cannot be linked to
+//! the group key, so the randomizer should only be shared with the
+//! participants of the session.
+
+use crate::evm;
+use frost::{Secp256K1Sha256, VerifyingKey, keys::VerifyingShare};
+
+/// The randomizer of a re-randomized signing session.
+pub type Randomizer = frost_rerandomized::Randomizer<Secp256K1Sha256>;
+
+/// The randomized key and randomizer point derived from a randomizer, which
+/// are needed for signing and aggregating in a re-randomized signing session.
+pub type RandomizedParams = frost_rerandomized::RandomizedParams<Secp256K1Sha256>;
+
+/// Generates a randomizer for a signing package.
+///
+/// Randomizers are sampled until the randomized key is supported by the EVM
+/// verifier, so that the resulting signature can be verified on-chain.
+pub fn randomizer(
+    key: &VerifyingKey,
+    signing: &frost::SigningPackage,
+) -> Result<Randomizer, frost::Error> {
+    loop {
+        let randomizer = Randomizer::new(rand::thread_rng(), signing)?;
+        if evm::verified_key(&randomized_key(key, randomizer)).is_ok() {
+            return Ok(randomizer);
+        }
+    }
+}
+
+/// Returns the randomized key of a group key.
+pub fn randomized_key(key: &VerifyingKey, randomizer: Randomizer) -> VerifyingKey {
+    *RandomizedParams::from_randomizer(key, randomizer).randomized_verifying_key()
+}
+
+/// Returns the randomized verifying share of a participant, which its signature
+/// shares in a re-randomized signing session are verified against.
+pub fn verifying_share(share: &VerifyingShare, params: &RandomizedParams) -> VerifyingShare {
+    VerifyingShare::new(share.to_element() + params.randomizer_element())
+}
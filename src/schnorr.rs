@@ -0,0 +1,284 @@
+//! Standalone FROST(secp256k1, SHA-256) Schnorr signatures and public keys, in
+//! the various encodings they are found in the wild.
+
+use crate::{address::Address, hex};
+use k256::elliptic_curve::sec1::{FromEncodedPoint as _, ToEncodedPoint as _};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// The affine coordinates of a point, which are not necessarily on the curve.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Point {
+    pub x: [u8; 32],
+    pub y: [u8; 32],
+}
+
+impl Point {
+    /// Creates a point from its 64-byte concatenated coordinates.
+    fn from_coords(bytes: &[u8]) -> Self {
+        Self {
+            x: bytes[..32].try_into().unwrap(),
+            y: bytes[32..64].try_into().unwrap(),
+        }
+    }
+
+    /// Creates a point from SEC1 compressed, SEC1 uncompressed or 64-byte
+    /// concatenated coordinates.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            33 => {
+                let point = k256::EncodedPoint::from_bytes(bytes).map_err(|_| Error::Encoding)?;
+                let point = Option::<k256::AffinePoint>::from(
+                    k256::AffinePoint::from_encoded_point(&point),
+                )
+                .ok_or(Error::NotOnCurve)?;
+                Ok(Self::from_coords(
+                    &point.to_encoded_point(false).as_bytes()[1..],
+                ))
+            }
+            65 if bytes[0] == 0x04 => Ok(Self::from_coords(&bytes[1..])),
+            64 => Ok(Self::from_coords(bytes)),
+            _ => Err(Error::Encoding),
+        }
+    }
+
+    /// Returns the curve point, or `None` if the coordinates are not a valid
+    /// secp256k1 point.
+    pub fn to_affine(self) -> Option<k256::AffinePoint> {
+        let point =
+            k256::EncodedPoint::from_affine_coordinates(&self.x.into(), &self.y.into(), false);
+        k256::AffinePoint::from_encoded_point(&point).into()
+    }
+
+    /// Returns the point as a FROST verifying key.
+    pub fn to_key(self) -> Result<frost::VerifyingKey, Error> {
+        let point = self.to_affine().ok_or(Error::NotOnCurve)?;
+        frost::VerifyingKey::deserialize(point.to_encoded_point(true).as_bytes())
+            .map_err(|_| Error::NotOnCurve)
+    }
+}
+
+impl FromStr for Point {
+    type Err = Error;
+
+    /// Parses a point as `{x,y}` coordinates, as displayed by `info`, or as
+    /// hexadecimal SEC1 compressed, SEC1 uncompressed or 64-byte concatenated
+    /// coordinates.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(coords) = s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            let (x, y) = coords.split_once(',').ok_or(Error::Encoding)?;
+            return Ok(Self {
+                x: decode_word(x.trim())?,
+                y: decode_word(y.trim())?,
+            });
+        }
+
+        Self::from_bytes(&hex::decode::<Vec<u8>>(s)?)
+    }
+}
+
+/// A public key, either as a point or identified only by its address.
+pub enum PublicKey {
+    Point(Point),
+    Address(Address),
+}
+
+impl PublicKey {
+    /// Returns whether or not the public key is the specified point.
+    pub fn matches(&self, point: &Point) -> bool {
+        match self {
+            Self::Point(p) => p == point,
+            Self::Address(address) => point
+                .to_key()
+                .is_ok_and(|key| Address::from_key(&key) == *address),
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    /// Parses a public key as an address, any of the point encodings, or the
+    /// ABI encoding of `info --abi-encode public-key`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('{') {
+            return s.parse().map(Self::Point);
+        }
+
+        let bytes = hex::decode::<Vec<u8>>(s)?;
+        match bytes.len() {
+            20 => Ok(Self::Address(Address::from_bytes(
+                bytes.try_into().unwrap(),
+            ))),
+            96 => {
+                if bytes[..12].iter().any(|&b| b != 0) {
+                    return Err(Error::Encoding);
+                }
+                let point = Point::from_coords(&bytes[32..]);
+                let address = Address::from_bytes(bytes[12..32].try_into().unwrap());
+                if !Self::Address(address).matches(&point) {
+                    return Err(Error::AddressMismatch);
+                }
+                Ok(Self::Point(point))
+            }
+            _ => Point::from_bytes(&bytes).map(Self::Point),
+        }
+    }
+}
+
+/// A signature, optionally including the public key of the signer.
+pub struct Signature {
+    pub public_key: Option<Point>,
+    pub r: Point,
+    pub z: [u8; 32],
+}
+
+impl Signature {
+    /// Returns the public key of the signer, either included in the signature
+    /// or specified explicitly; both must match if both are present.
+    pub fn signer(&self, public_key: Option<&PublicKey>) -> Result<Point, Error> {
+        match (public_key, &self.public_key) {
+            (Some(public_key), Some(point)) if public_key.matches(point) => Ok(*point),
+            (Some(_), Some(_)) => Err(Error::PublicKeyMismatch),
+            (Some(PublicKey::Point(point)), None) | (None, Some(point)) => Ok(*point),
+            (Some(PublicKey::Address(_)), None) | (None, None) => Err(Error::MissingPublicKey),
+        }
+    }
+
+    /// Creates a signature from a serialized FROST signature, or the ABI
+    /// encoding of its `R` coordinates and `z` scalar, optionally preceded by
+    /// the public key coordinates.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            65 => Ok(Self {
+                public_key: None,
+                r: Point::from_bytes(&bytes[..33])?,
+                z: bytes[33..].try_into().unwrap(),
+            }),
+            96 => Ok(Self {
+                public_key: None,
+                r: Point::from_coords(&bytes[..64]),
+                z: bytes[64..].try_into().unwrap(),
+            }),
+            160 => Ok(Self {
+                public_key: Some(Point::from_coords(&bytes[..64])),
+                r: Point::from_coords(&bytes[64..128]),
+                z: bytes[128..].try_into().unwrap(),
+            }),
+            _ => Err(Error::Encoding),
+        }
+    }
+
+    /// Returns the signature as a FROST signature.
+    pub fn to_frost(&self) -> Result<frost::Signature, Error> {
+        let r = self.r.to_affine().ok_or(Error::NotOnCurve)?;
+        let bytes = [r.to_encoded_point(true).as_bytes(), &self.z].concat();
+        frost::Signature::deserialize(&bytes).map_err(|_| Error::InvalidScalar)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = Error;
+
+    /// Parses a signature as `R,z` with any of the point encodings for `R`, as
+    /// a serialized FROST signature, or as the ABI encoding of
+    /// `info --abi-encode signature` with or without the public key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((r, z)) = s.rsplit_once(',')
+            && !z.ends_with('}')
+        {
+            return Ok(Self {
+                public_key: None,
+                r: r.trim().parse()?,
+                z: decode_word(z.trim())?,
+            });
+        }
+
+        Self::from_bytes(&hex::decode::<Vec<u8>>(s)?)
+    }
+}
+
+/// Verifies a batch of signatures, returning the indices of the invalid ones.
+///
+/// The signatures are verified together with the FROST batch verifier, which is
+/// significantly faster than verifying them one by one. Only when the batch is
+/// invalid are the signatures verified individually, in order to identify the
+/// invalid ones.
+pub fn verify_batch<M>(entries: &[(frost::VerifyingKey, M, frost::Signature)]) -> Vec<usize>
+where
+    M: AsRef<[u8]>,
+{
+    let items = entries
+        .iter()
+        .map(|(key, message, signature)| {
+            frost_core::batch::Item::<frost::Secp256K1Sha256>::new(*key, *signature, message)
+        })
+        .collect::<Vec<_>>();
+
+    let mut verifier = frost_core::batch::Verifier::new();
+    for item in items.iter().flatten() {
+        verifier.queue(item.clone());
+    }
+    if items.iter().all(Result::is_ok) && verifier.verify(rand::thread_rng()).is_ok() {
+        return Vec::new();
+    }
+
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            item.as_ref()
+                .map_or(true, |item| item.clone().verify_single().is_err())
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn decode_word(s: &str) -> Result<[u8; 32], Error> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    if digits.len() > 64 {
+        return Err(Error::Encoding);
+    }
+    Ok(hex::decode(&format!("{digits:0>64}"))?)
+}
+
+/// An error parsing or using a standalone signature or public key.
+#[derive(Debug)]
+pub enum Error {
+    /// The value is not in any of the supported encodings.
+    Encoding,
+    /// The point is not on the secp256k1 curve.
+    NotOnCurve,
+    /// The signature scalar is not smaller than the curve order.
+    InvalidScalar,
+    /// The ABI encoded address does not match the public key.
+    AddressMismatch,
+    /// The public key does not match the public key included in the signature.
+    PublicKeyMismatch,
+    /// The signature does not include the public key, and no public key point
+    /// was specified.
+    MissingPublicKey,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Encoding => "unsupported encoding",
+            Self::NotOnCurve => "point is not on the secp256k1 curve",
+            Self::InvalidScalar => "signature scalar is not smaller than the curve order",
+            Self::AddressMismatch => "address does not match the public key",
+            Self::PublicKeyMismatch => "public key does not match the signature's public key",
+            Self::MissingPublicKey => "public key point is required to verify the signature",
+        })
+    }
+}
+
+impl From<hex::DecodeError> for Error {
+    fn from(_: hex::DecodeError) -> Self {
+        Self::Encoding
+    }
+}
+
+impl std::error::Error for Error {}
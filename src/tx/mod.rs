@@ -0,0 +1,250 @@
+//! Transactions that can be attached to a signing session, so that signers can
+//! inspect what the signing message actually authorizes.
+
+mod policy;
+mod safe;
+mod user_op;
+
+pub use self::{
+    policy::Policy,
+    safe::{OwnerSignature, SafeTransaction},
+    user_op::UserOperation,
+};
+use crate::{address::Address, fmt::Hex, hex, keccak};
+use frost::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A transaction that is signed by a FROST signature.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde", tag = "type", rename_all = "camelCase")]
+pub enum Transaction {
+    /// A Safe transaction, signed over its `safeTxHash`.
+    Safe(SafeTransaction),
+    /// An ERC-4337 user operation, signed over its `userOpHash`.
+    UserOperation(UserOperation),
+}
+
+impl Transaction {
+    /// Computes the signing message for the transaction.
+    pub fn hash(&self) -> Result<[u8; 32], Error> {
+        match self {
+            Self::Safe(tx) => Ok(tx.hash()),
+            Self::UserOperation(op) => op.hash(),
+        }
+    }
+
+    /// Serialize the transaction into pretty-printed JSON.
+    pub fn serialize(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec_pretty(self)
+    }
+
+    /// Deserialize a transaction from JSON, filling in fields that it omits
+    /// from the specified defaults.
+    pub fn deserialize_with(data: &[u8], defaults: &Defaults) -> Result<Self, serde_json::Error> {
+        let mut value = serde_json::from_slice::<serde_json::Value>(data)?;
+        if let Some(object) = value.as_object_mut() {
+            let fields = match object.get("type").and_then(|kind| kind.as_str()) {
+                Some("safe") => [
+                    ("chainId", defaults.chain_id.map(|id| id.to_string())),
+                    ("safe", defaults.safe.map(|safe| safe.to_string())),
+                ],
+                Some("userOperation") => [
+                    ("chainId", defaults.chain_id.map(|id| id.to_string())),
+                    (
+                        "entryPoint",
+                        defaults.entry_point.map(|entry| entry.to_string()),
+                    ),
+                ],
+                _ => Default::default(),
+            };
+            for (field, default) in fields {
+                if let Some(default) = default {
+                    object.entry(field).or_insert(default.into());
+                }
+            }
+        }
+        serde_json::from_value(value)
+    }
+}
+
+/// Defaults for fields of transactions that are the same for all transactions
+/// of a Safe, and so can be configured instead.
+#[derive(Default)]
+pub struct Defaults {
+    pub chain_id: Option<Uint>,
+    pub safe: Option<Address>,
+    pub entry_point: Option<Address>,
+}
+
+impl Display for Transaction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Safe(tx) => tx.fmt(f),
+            Self::UserOperation(op) => op.fmt(f),
+        }
+    }
+}
+
+/// Computes an EIP-712 signing hash from a domain separator and struct hash.
+fn eip712_hash(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(66);
+    buf.extend_from_slice(b"\x19\x01");
+    buf.extend_from_slice(domain_separator);
+    buf.extend_from_slice(struct_hash);
+    keccak::v256(&buf)
+}
+
+/// A 256-bit unsigned integer, stored as big-endian bytes.
+///
+/// It is serialized as a decimal string, and deserialized from either a JSON
+/// number or a decimal or `0x`-prefixed hexadecimal string.
+#[derive(Clone, Copy, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Uint(pub [u8; 32]);
+
+impl Uint {
+    /// Returns the ABI encoding of the integer.
+    pub fn abi(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl From<u64> for Uint {
+    fn from(value: u64) -> Self {
+        let mut bytes = [0; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        Self(bytes)
+    }
+}
+
+impl FromStr for Uint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(digits) = s.strip_prefix("0x") {
+            let padded = format!("{digits:0>64}");
+            return hex::decode(&padded)
+                .map(Self)
+                .map_err(|e| format!("invalid integer: {e}"));
+        }
+
+        if s.is_empty() {
+            return Err("invalid integer: empty string".to_owned());
+        }
+        let mut bytes = [0_u8; 32];
+        for digit in s.bytes() {
+            let mut carry = match digit {
+                b'0'..=b'9' => u16::from(digit - b'0'),
+                _ => return Err(format!("invalid integer: invalid digit {digit:#02x}")),
+            };
+            for byte in bytes.iter_mut().rev() {
+                let value = u16::from(*byte) * 10 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return Err("invalid integer: overflows 256 bits".to_owned());
+            }
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl Display for Uint {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut bytes = self.0;
+        let mut digits = Vec::new();
+        while bytes.iter().any(|&b| b != 0) || digits.is_empty() {
+            let mut remainder = 0_u16;
+            for byte in &mut bytes {
+                let value = (remainder << 8) | u16::from(*byte);
+                *byte = (value / 10) as u8;
+                remainder = value % 10;
+            }
+            digits.push(b'0' + remainder as u8);
+        }
+        digits.reverse();
+        f.write_str(std::str::from_utf8(&digits).unwrap())
+    }
+}
+
+impl Serialize for Uint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(crate = "::frost::serde", untagged)]
+        enum Repr {
+            Number(u64),
+            String(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(value.into()),
+            Repr::String(value) => value.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Arbitrary bytes, serialized as a `0x`-prefixed hexadecimal string.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#}", Hex(&self.0))
+    }
+}
+
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(&s)
+            .map(Self)
+            .map_err(|e| de::Error::custom(format!("invalid bytes: {e}")))
+    }
+}
+
+/// An error computing the signing message of a transaction.
+#[derive(Debug)]
+pub enum Error {
+    /// The user operation uses an EIP-7702 `initCode`, but the account's
+    /// delegate was not specified.
+    MissingDelegate,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::MissingDelegate => {
+                f.write_str("user operation with EIP-7702 initCode requires an `eip7702Delegate`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,133 @@
+use super::{SafeTransaction, Transaction, Uint, UserOperation};
+use crate::{address::Address, fmt::Hex, hex};
+use frost::serde::{Deserialize, Deserializer, de};
+use std::fmt::{self, Display, Formatter};
+
+/// A signing policy, restricting which transactions a signer approves.
+///
+/// Restrictions that are not specified allow any value, with the exception of
+/// `DELEGATECALL` operations which must be explicitly allowed.
+#[derive(Deserialize)]
+#[serde(
+    crate = "::frost::serde",
+    rename_all = "camelCase",
+    deny_unknown_fields
+)]
+pub struct Policy {
+    /// The allowed chain IDs.
+    chain_ids: Option<Vec<Uint>>,
+    /// The allowed Safe accounts for Safe transactions.
+    safes: Option<Vec<Address>>,
+    /// The allowed senders for user operations.
+    senders: Option<Vec<Address>>,
+    /// The allowed call targets.
+    to: Option<Vec<Address>>,
+    /// The maximum value that a transaction may transfer.
+    max_value: Option<Uint>,
+    /// Whether or not Safe transactions may use `DELEGATECALL` operations.
+    #[serde(default)]
+    allow_delegatecall: bool,
+    /// The allowed function selectors for user operation call data.
+    selectors: Option<Vec<Selector>>,
+}
+
+impl Policy {
+    /// Deserialize a policy from JSON.
+    pub fn deserialize(data: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(data)
+    }
+
+    /// Evaluates the policy for a transaction, returning the reasons for
+    /// refusing it; the transaction is allowed if there are none.
+    pub fn evaluate(&self, transaction: &Transaction) -> Vec<String> {
+        let mut reasons = Vec::new();
+        match transaction {
+            Transaction::Safe(tx) => self.evaluate_safe(tx, &mut reasons),
+            Transaction::UserOperation(op) => self.evaluate_user_op(op, &mut reasons),
+        }
+        reasons
+    }
+
+    fn evaluate_safe(&self, tx: &SafeTransaction, reasons: &mut Vec<String>) {
+        self.check_chain_id(&tx.chain_id, reasons);
+        if !allows(&self.safes, &tx.safe) {
+            reasons.push(format!("Safe {} is not allowed", tx.safe));
+        }
+        self.check_call(&tx.to, &tx.value, reasons);
+        if tx.operation == SafeTransaction::DELEGATECALL && !self.allow_delegatecall {
+            reasons.push("DELEGATECALL operations are not allowed".to_owned());
+        }
+    }
+
+    fn evaluate_user_op(&self, op: &UserOperation, reasons: &mut Vec<String>) {
+        self.check_chain_id(&op.chain_id, reasons);
+        if !allows(&self.senders, &op.sender) {
+            reasons.push(format!("sender {} is not allowed", op.sender));
+        }
+        if let Some(selectors) = &self.selectors {
+            match op.selector() {
+                Some(selector) if selectors.contains(&Selector(selector)) => {}
+                Some(selector) => reasons.push(format!(
+                    "function selector {} is not allowed",
+                    Selector(selector)
+                )),
+                None => reasons.push("call data has no function selector".to_owned()),
+            }
+        }
+
+        // The call target and value are only known for calls to the account's
+        // `execute` function, so refuse anything else when they are restricted.
+        match op.execute_call() {
+            Some((to, value)) => self.check_call(&to, &value, reasons),
+            None if self.to.is_some() || self.max_value.is_some() => reasons
+                .push("call target and value cannot be determined from the call data".to_owned()),
+            None => {}
+        }
+    }
+
+    fn check_chain_id(&self, chain_id: &Uint, reasons: &mut Vec<String>) {
+        if !allows(&self.chain_ids, chain_id) {
+            reasons.push(format!("chain ID {chain_id} is not allowed"));
+        }
+    }
+
+    fn check_call(&self, to: &Address, value: &Uint, reasons: &mut Vec<String>) {
+        if !allows(&self.to, to) {
+            reasons.push(format!("call target {to} is not allowed"));
+        }
+        if let Some(max_value) = &self.max_value
+            && value > max_value
+        {
+            reasons.push(format!("value {value} exceeds the maximum of {max_value}"));
+        }
+    }
+}
+
+/// Returns whether or not an optional allowlist allows a value.
+fn allows<T: PartialEq>(allowlist: &Option<Vec<T>>, value: &T) -> bool {
+    allowlist
+        .as_ref()
+        .is_none_or(|allowed| allowed.contains(value))
+}
+
+/// A 4-byte function selector.
+#[derive(PartialEq)]
+struct Selector([u8; 4]);
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#}", Hex(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Selector {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(&s)
+            .map(Self)
+            .map_err(|e| de::Error::custom(format!("invalid selector: {e}")))
+    }
+}
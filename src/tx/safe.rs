@@ -0,0 +1,212 @@
+use super::{Bytes, Uint};
+use crate::{abi, address::Address, keccak};
+use frost::serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A Safe transaction.
+///
+/// This uses the same field names as the Safe Transaction Service, so that
+/// transactions can be copied from it directly.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+pub struct SafeTransaction {
+    pub chain_id: Uint,
+    pub safe: Address,
+    pub to: Address,
+    #[serde(default)]
+    pub value: Uint,
+    #[serde(default)]
+    pub data: Bytes,
+    #[serde(default)]
+    pub operation: u8,
+    #[serde(default)]
+    pub safe_tx_gas: Uint,
+    #[serde(default)]
+    pub base_gas: Uint,
+    #[serde(default)]
+    pub gas_price: Uint,
+    #[serde(default)]
+    pub gas_token: Address,
+    #[serde(default)]
+    pub refund_receiver: Address,
+    pub nonce: Uint,
+}
+
+impl SafeTransaction {
+    /// The `DELEGATECALL` operation.
+    pub const DELEGATECALL: u8 = 1;
+
+    /// Decodes a Safe transaction and its signatures from `execTransaction`
+    /// calldata. The chain ID, Safe and nonce are not part of the calldata,
+    /// and so need to be specified separately.
+    pub fn decode_exec_transaction(
+        calldata: &[u8],
+        chain_id: Uint,
+        safe: Address,
+        nonce: Uint,
+    ) -> Option<(Self, &[u8])> {
+        let selector = keccak::v256(
+            b"execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,\
+              address,address,bytes)",
+        );
+        let args = calldata.strip_prefix(&selector[..4])?;
+        let transaction = Self {
+            chain_id,
+            safe,
+            to: abi::decode_address(args, 0)?,
+            value: Uint(abi::decode_word(args, 1)?),
+            data: Bytes(abi::decode_bytes(args, 2)?.to_vec()),
+            operation: abi::decode_uint(args, 3)?.try_into().ok()?,
+            safe_tx_gas: Uint(abi::decode_word(args, 4)?),
+            base_gas: Uint(abi::decode_word(args, 5)?),
+            gas_price: Uint(abi::decode_word(args, 6)?),
+            gas_token: abi::decode_address(args, 7)?,
+            refund_receiver: abi::decode_address(args, 8)?,
+            nonce,
+        };
+        Some((transaction, abi::decode_bytes(args, 9)?))
+    }
+
+    /// Computes the EIP-712 `safeTxHash` of the transaction, for Safe v1.3.0
+    /// and later.
+    pub fn hash(&self) -> [u8; 32] {
+        let domain_separator = keccak::v256(
+            &[
+                keccak::v256(b"EIP712Domain(uint256 chainId,address verifyingContract)"),
+                self.chain_id.abi(),
+                abi::address(self.safe),
+            ]
+            .concat(),
+        );
+        let struct_hash = keccak::v256(
+            &[
+                keccak::v256(
+                    b"SafeTx(address to,uint256 value,bytes data,uint8 operation,\
+                      uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,\
+                      address gasToken,address refundReceiver,uint256 nonce)",
+                ),
+                abi::address(self.to),
+                self.value.abi(),
+                keccak::v256(&self.data.0),
+                abi::uint(self.operation.into()),
+                self.safe_tx_gas.abi(),
+                self.base_gas.abi(),
+                self.gas_price.abi(),
+                abi::address(self.gas_token),
+                abi::address(self.refund_receiver),
+                self.nonce.abi(),
+            ]
+            .concat(),
+        );
+        super::eip712_hash(&domain_separator, &struct_hash)
+    }
+}
+
+impl Display for SafeTransaction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let operation = match self.operation {
+            0 => "CALL",
+            Self::DELEGATECALL => "DELEGATECALL",
+            _ => "UNKNOWN",
+        };
+        writeln!(f, "Safe transaction:")?;
+        writeln!(f, "  chain id:        {}", self.chain_id)?;
+        writeln!(f, "  safe:            {}", self.safe)?;
+        writeln!(f, "  to:              {}", self.to)?;
+        writeln!(f, "  value:           {}", self.value)?;
+        writeln!(f, "  data:            {}", self.data)?;
+        writeln!(f, "  operation:       {} ({operation})", self.operation)?;
+        writeln!(f, "  safe tx gas:     {}", self.safe_tx_gas)?;
+        writeln!(f, "  base gas:        {}", self.base_gas)?;
+        writeln!(f, "  gas price:       {}", self.gas_price)?;
+        writeln!(f, "  gas token:       {}", self.gas_token)?;
+        writeln!(f, "  refund receiver: {}", self.refund_receiver)?;
+        write!(f, "  nonce:           {}", self.nonce)
+    }
+}
+
+/// A Safe owner signature, as encoded in `execTransaction` signatures.
+pub enum OwnerSignature {
+    /// An EIP-1271 contract signature, such as from a `SafeFROSTSigner`.
+    Contract { owner: Address, signature: Vec<u8> },
+    /// A hash that was approved on-chain by the owner.
+    ApprovedHash { owner: Address },
+    /// An ECDSA signature, either of the `safeTxHash` itself or of its
+    /// `eth_sign` message when `v > 30`.
+    Ecdsa { r: [u8; 32], s: [u8; 32], v: u8 },
+}
+
+impl OwnerSignature {
+    /// Decodes the owner signatures of `execTransaction` signatures bytes.
+    ///
+    /// The number of owner signatures is not encoded, so they are read until
+    /// the first contract signature data. When `co_signed`, the signatures end
+    /// with a 96-byte `SafeFROSTCoSigner` co-signature, which is returned
+    /// separately.
+    pub fn decode_all(
+        signatures: &[u8],
+        co_signed: bool,
+    ) -> Option<(Vec<OwnerSignature>, Option<&[u8]>)> {
+        let (signatures, co_signature) = if co_signed {
+            let (signatures, co_signature) =
+                signatures.split_at_checked(signatures.len().checked_sub(96)?)?;
+            (signatures, Some(co_signature))
+        } else {
+            (signatures, None)
+        };
+
+        let mut owners = Vec::new();
+        let mut end = signatures.len();
+        let mut offset = 0;
+        while offset + 65 <= end {
+            let r = &signatures[offset..offset + 32];
+            let s = &signatures[offset + 32..offset + 64];
+            let v = signatures[offset + 64];
+            let owner = || abi::decode_address(r, 0);
+            owners.push(match v {
+                0 => {
+                    let position = usize::try_from(abi::decode_uint(s, 0)?).ok()?;
+                    end = end.min(position);
+                    let data = signatures.get(position..)?;
+                    let len = usize::try_from(abi::decode_uint(data, 0)?).ok()?;
+                    Self::Contract {
+                        owner: owner()?,
+                        signature: data.get(32..)?.get(..len)?.to_vec(),
+                    }
+                }
+                1 => Self::ApprovedHash { owner: owner()? },
+                v => Self::Ecdsa {
+                    r: r.try_into().unwrap(),
+                    s: s.try_into().unwrap(),
+                    v,
+                },
+            });
+            offset += 65;
+        }
+        Some((owners, co_signature))
+    }
+
+    /// Returns the owner that signed the `safeTxHash`, if it can be determined
+    /// from the signature alone.
+    pub fn owner(&self, safe_tx_hash: &[u8; 32]) -> Option<Address> {
+        match self {
+            Self::Contract { owner, .. } | Self::ApprovedHash { owner } => Some(*owner),
+            Self::Ecdsa { r, s, v } => {
+                let (hash, v) = if *v > 30 {
+                    let message = [b"\x19Ethereum Signed Message:\n32".as_slice(), safe_tx_hash];
+                    (keccak::v256(&message.concat()), v - 31)
+                } else {
+                    (*safe_tx_hash, v.checked_sub(27)?)
+                };
+                let signature = k256::ecdsa::Signature::from_scalars(*r, *s).ok()?;
+                let recovery_id = k256::ecdsa::RecoveryId::from_byte(v)?;
+                let key =
+                    k256::ecdsa::VerifyingKey::recover_from_prehash(&hash, &signature, recovery_id)
+                        .ok()?;
+                let point = key.to_encoded_point(false);
+                let digest = keccak::v256(&point.as_bytes()[1..]);
+                Some(Address::from_bytes(digest[12..].try_into().unwrap()))
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Bytes, Error, Uint};
+use crate::{abi, address::Address, fmt::Hex, keccak};
+use frost::serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// An ERC-4337 packed user operation for the v0.8 entry point.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "::frost::serde", rename_all = "camelCase")]
+pub struct UserOperation {
+    pub chain_id: Uint,
+    pub entry_point: Address,
+    pub sender: Address,
+    pub nonce: Uint,
+    #[serde(default)]
+    pub init_code: Bytes,
+    #[serde(default)]
+    pub call_data: Bytes,
+    pub account_gas_limits: Uint,
+    pub pre_verification_gas: Uint,
+    pub gas_fees: Uint,
+    #[serde(default)]
+    pub paymaster_and_data: Bytes,
+    /// The EIP-7702 delegate of the sender, required when the `initCode`
+    /// starts with the EIP-7702 marker, as the entry point substitutes it into
+    /// the user operation hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip7702_delegate: Option<Address>,
+}
+
+impl UserOperation {
+    /// Computes the EIP-712 `userOpHash` of the user operation.
+    pub fn hash(&self) -> Result<[u8; 32], Error> {
+        let domain_separator = keccak::v256(
+            &[
+                keccak::v256(
+                    b"EIP712Domain(string name,string version,uint256 chainId,\
+                      address verifyingContract)",
+                ),
+                keccak::v256(b"ERC4337"),
+                keccak::v256(b"1"),
+                self.chain_id.abi(),
+                abi::address(self.entry_point),
+            ]
+            .concat(),
+        );
+        let struct_hash = keccak::v256(
+            &[
+                keccak::v256(
+                    b"PackedUserOperation(address sender,uint256 nonce,bytes initCode,\
+                      bytes callData,bytes32 accountGasLimits,uint256 preVerificationGas,\
+                      bytes32 gasFees,bytes paymasterAndData)",
+                ),
+                abi::address(self.sender),
+                self.nonce.abi(),
+                self.init_code_hash()?,
+                keccak::v256(&self.call_data.0),
+                self.account_gas_limits.abi(),
+                self.pre_verification_gas.abi(),
+                self.gas_fees.abi(),
+                keccak::v256(&self.paymaster_and_data.0),
+            ]
+            .concat(),
+        );
+        Ok(super::eip712_hash(&domain_separator, &struct_hash))
+    }
+
+    /// Returns the function selector of the call data, if any.
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        self.call_data.0.get(..4)?.try_into().ok()
+    }
+
+    /// Returns the target and value of the call when the call data is for the
+    /// `FROSTAccount.execute(address,uint256,bytes)` function.
+    pub fn execute_call(&self) -> Option<(Address, Uint)> {
+        let execute = keccak::v256(b"execute(address,uint256,bytes)");
+        if self.selector()? != execute[..4] {
+            return None;
+        }
+        let target = self.call_data.0.get(4..36)?;
+        let value = self.call_data.0.get(36..68)?;
+        if target[..12].iter().any(|&b| b != 0) {
+            return None;
+        }
+        Some((
+            Address::from_bytes(target[12..].try_into().unwrap()),
+            Uint(value.try_into().unwrap()),
+        ))
+    }
+
+    fn init_code_hash(&self) -> Result<[u8; 32], Error> {
+        let init_code = &self.init_code.0;
+        let marker = init_code.len() >= 2
+            && init_code[..2] == [0x77, 0x02]
+            && init_code[2..init_code.len().min(20)]
+                .iter()
+                .all(|&b| b == 0);
+        if !marker {
+            return Ok(keccak::v256(init_code));
+        }
+
+        let delegate = self.eip7702_delegate.ok_or(Error::MissingDelegate)?;
+        let rest = init_code.get(20..).unwrap_or_default();
+        Ok(keccak::v256(&[delegate.as_slice(), rest].concat()))
+    }
+}
+
+impl Display for UserOperation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "user operation:")?;
+        writeln!(f, "  chain id:             {}", self.chain_id)?;
+        writeln!(f, "  entry point:          {}", self.entry_point)?;
+        writeln!(f, "  sender:               {}", self.sender)?;
+        writeln!(f, "  nonce:                {}", self.nonce)?;
+        writeln!(f, "  init code:            {}", self.init_code)?;
+        if let Some(delegate) = &self.eip7702_delegate {
+            writeln!(f, "  EIP-7702 delegate:    {delegate}")?;
+        }
+        writeln!(f, "  call data:            {}", self.call_data)?;
+        writeln!(
+            f,
+            "  account gas limits:   {:#}",
+            Hex(&self.account_gas_limits.0)
+        )?;
+        writeln!(f, "  pre-verification gas: {}", self.pre_verification_gas)?;
+        writeln!(f, "  gas fees:             {:#}", Hex(&self.gas_fees.0))?;
+        write!(f, "  paymaster and data:   {}", self.paymaster_and_data)
+    }
+}
@@ -1,8 +1,9 @@
 use rand::{Rng as _, seq::SliceRandom as _};
 use std::{
     fmt::Write as _,
+    io::Write as _,
     path::Path,
-    process::{Command, Stdio},
+    process::{Command, Output, Stdio},
 };
 
 /// Generate and verify a FROST signature.
@@ -19,53 +20,1366 @@ fn roundtrip() {
     let message = random_message();
     let participants = random_signers(3, 5);
 
-    // Round 1.
-    for participant in &participants {
+    // Round 1, where the signing package is only prepared once enough
+    // participants committed.
+    for (i, participant) in participants.iter().enumerate() {
         safe_frost.exec("commit", &["--share-index", participant]);
+        if i == 0 {
+            safe_frost.fail("prepare", &["--threshold", "3", "--message", &message]);
+        }
     }
-    safe_frost.exec("prepare", &["--message", &message]);
+    // The root directory has no recorded threshold, which the coordinator
+    // never reads from key shares.
+    safe_frost.fail("prepare", &["--message", &message]);
+    safe_frost.exec("prepare", &["--threshold", "3", "--message", &message]);
 
     // Round 2.
     for participant in &participants {
-        safe_frost.exec("sign", &["--share-index", participant]);
+        safe_frost.exec(
+            "sign",
+            &["--share-index", participant, "--expect-message", &message],
+        );
     }
     safe_frost.exec("aggregate", &[]);
 
     // Verify the signature.
     safe_frost.exec("verify", &[]);
     safe_frost.exec("info", &["signature"]);
+
+    // Verify the signature standalone, from its ABI encoded form.
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let public_key = stdout("info", &["--abi-encode", "public-key"]);
+    let signature = stdout("info", &["--abi-encode", "signature"]);
+    safe_frost.exec(
+        "verify",
+        &[
+            "--signature",
+            &signature,
+            "--message",
+            &message,
+            "--public-key",
+            &public_key,
+        ],
+    );
+    safe_frost.fail(
+        "verify",
+        &[
+            "--signature",
+            &signature,
+            "--message",
+            &random_message(),
+            "--public-key",
+            &public_key,
+        ],
+    );
+
+    // Verify a batch of signature records, identifying the invalid ones.
+    let signature_with_key = stdout("info", &["--abi-encode", "signature", "--with-public-key"]);
+    let valid = format!(
+        "{{\"publicKey\":\"{public_key}\",\"message\":\"{message}\",\"signature\":\"{signature}\"}}\n\
+         {message}{signature_with_key}\n",
+    );
+    let batch = format!("{}/batch", safe_frost.root);
+    std::fs::write(&batch, &valid).unwrap();
+    safe_frost.exec("verify", &["--batch", &batch]);
+    safe_frost.fail("verify", &["--batch", &batch, "--public-key", &public_key]);
+    std::fs::write(
+        &batch,
+        format!("{valid}{}{signature_with_key}\n", random_message()),
+    )
+    .unwrap();
+    safe_frost.fail("verify", &["--batch", &batch]);
+}
+
+/// Generate and verify a FROST signature, streaming intermediate files over
+/// standard input and output instead of the root directory.
+#[test]
+fn streaming() {
+    let safe_frost = SafeFrost::with_root_directory("streaming");
+
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let message = random_message();
+    let participants = random_signers(2, 3);
+
+    let mut commitments = Vec::new();
+    for participant in &participants {
+        commitments.extend(safe_frost.pipe(
+            "commit",
+            &["--share-index", participant, "--out", "-"],
+            &[],
+        ));
+    }
+    // Standard input can only be used for a single input of a command.
+    let output = safe_frost.run(
+        "prepare",
+        &[
+            "--threshold",
+            "2",
+            "--message",
+            &message,
+            "--in",
+            "-",
+            "--in",
+            "-",
+        ],
+        &commitments,
+    );
+    assert!(!output.status.success());
+    safe_frost.pipe(
+        "prepare",
+        &["--threshold", "2", "--message", &message, "--in", "-"],
+        &commitments,
+    );
+
+    let mut shares = Vec::new();
+    for participant in &participants {
+        shares.extend(safe_frost.pipe(
+            "sign",
+            &["--share-index", participant, "--out", "-", "--yes"],
+            &[],
+        ));
+    }
+    // Signature shares of a participant are never silently replaced.
+    let output = safe_frost.run(
+        "aggregate",
+        &["--in", "-", "--out", "-"],
+        &[&shares[..], &shares[..]].concat(),
+    );
+    assert!(!output.status.success());
+    let signature = safe_frost.pipe("aggregate", &["--in", "-", "--out", "-"], &shares);
+
+    safe_frost.pipe("verify", &["--in", "-"], &signature);
+}
+
+/// Generate and verify a FROST signature with separate workspaces for each
+/// role, where files are explicitly exchanged between them.
+#[test]
+fn roles() {
+    let safe_frost = SafeFrost::with_root_directory("roles").layout("roles");
+
+    safe_frost.exec(
+        "split",
+        &["--threshold", "2", "--signers", "3", "--export-bundles"],
+    );
+
+    let message = random_message();
+    let participants = random_signers(2, 3);
+
+    let signer =
+        |participant: &str, file: &str| format!("{}/signer-{participant}/{file}", safe_frost.root);
+    let coordinator = |file: &str| format!("{}/coordinator/{file}", safe_frost.root);
+
+    // Signers check their key shares after receiving them from the dealer.
+    for participant in ["0", "1", "2"] {
+        safe_frost.exec("verify", &["key", "--share-index", participant]);
+    }
+
+    // The session status tells each role what to do next.
+    let status = || {
+        safe_frost.exec("status", &[]);
+        serde_json::from_slice::<serde_json::Value>(&safe_frost.pipe("status", &["--json"], &[]))
+            .unwrap()
+    };
+    let committed = status();
+    assert_eq!(committed["stage"], "commit");
+    assert!(committed.get("threshold").is_none());
+
+    for participant in &participants {
+        let commitments = coordinator(&format!("round1.{participant}.commitments"));
+        safe_frost.exec(
+            "commit",
+            &["--share-index", participant, "--out", &commitments],
+        );
+    }
+
+    // The group overview shows which participants are ready to sign, and key
+    // shares can be inspected without revealing the secret.
+    safe_frost.exec("info", &["group"]);
+    let group_info = |options: &[&str]| {
+        serde_json::from_slice::<serde_json::Value>(&safe_frost.pipe(
+            "info",
+            &[&["--json", "group"], options].concat(),
+            &[],
+        ))
+        .unwrap()
+    };
+    // Without a recorded threshold, it is only read from a key share on
+    // request.
+    assert!(group_info(&[]).get("threshold").is_none());
+    let group = group_info(&["--share-index", "0"]);
+    assert_eq!(group["threshold"], 2);
+    for (index, share) in group["shares"].as_array().unwrap().iter().enumerate() {
+        let participating = participants.contains(&index.to_string());
+        assert_eq!(share["keyShare"], true);
+        assert_eq!(share["nonces"], participating);
+        assert_eq!(share["commitments"], participating);
+    }
+    let key_share = serde_json::from_slice::<serde_json::Value>(&safe_frost.pipe(
+        "info",
+        &["--json", "key-share", "--share-index", "1"],
+        &[],
+    ))
+    .unwrap();
+    assert_eq!(key_share["identifier"], "2");
+    assert_eq!(key_share["minSigners"], 2);
+    assert_eq!(key_share["publicKey"]["address"], group["address"]);
+    assert_eq!(
+        key_share["verifyingShare"],
+        group["shares"][1]["verifyingShare"]
+    );
+    safe_frost.fail(
+        "info",
+        &[
+            "key-share",
+            "--share-index",
+            "0",
+            "--in",
+            &signer("1", "key.1"),
+        ],
+    );
+
+    // Without a recorded threshold, status cannot tell whether enough
+    // participants have committed.
+    let committed = status();
+    assert_eq!(committed["stage"], "commit");
+    assert!(committed.get("threshold").is_none());
+    safe_frost.exec("prepare", &["--threshold", "2", "--message", &message]);
+    let pending = status();
+    assert_eq!(pending["stage"], "sign");
+    assert_eq!(pending["message"], format!("0x{message}"));
+    assert_eq!(pending["nextSteps"].as_array().unwrap().len(), 3);
+
+    for participant in &participants {
+        let share = coordinator(&format!("round2.{participant}"));
+        safe_frost.exec(
+            "sign",
+            &[
+                "--share-index",
+                participant,
+                "--in",
+                &coordinator("round1"),
+                "--out",
+                &share,
+                "--expect-message",
+                &message,
+            ],
+        );
+
+        // The coordinator can check each share as soon as it arrives.
+        safe_frost.exec("verify", &["share", "--share-index", participant]);
+    }
+    assert_eq!(status()["stage"], "aggregate");
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+    let done = status();
+    assert_eq!(done["stage"], "done");
+    assert_eq!(done["signature"], "valid");
+
+    // Signers refuse to use a workspace holding another signer's key share.
+    let (this, other) = (&participants[0], &participants[1]);
+    std::fs::copy(
+        signer(other, &format!("key.{other}")),
+        signer(this, &format!("key.{other}")),
+    )
+    .unwrap();
+    safe_frost.fail("commit", &["--share-index", this]);
+}
+
+/// Manage multiple named groups in a single root directory.
+#[test]
+fn groups() {
+    let safe_frost = SafeFrost::with_root_directory("groups");
+
+    for group in ["treasury", "payroll"] {
+        safe_frost
+            .group(group)
+            .exec("split", &["--threshold", "2", "--signers", "3"]);
+    }
+    safe_frost.exec("groups", &["list"]);
+    safe_frost.group("treasury").exec("groups", &["show"]);
+
+    let treasury = safe_frost.group("treasury");
+    for participant in ["0", "1", "2"] {
+        treasury.exec("commit", &["--share-index", participant]);
+    }
+
+    // The coordinator selects participants by their share indexes, and needs
+    // to include at least the group's threshold of distinct participants.
+    let message = random_message();
+    let participants = random_signers(2, 3);
+    let commitments = |participant: &str| {
+        format!(
+            "{}/groups/treasury/round1.{participant}.commitments",
+            safe_frost.root,
+        )
+    };
+    treasury.fail(
+        "prepare",
+        &["--message", &message, "--participants", &participants[0]],
+    );
+    treasury.fail(
+        "prepare",
+        &[
+            "--message",
+            &message,
+            "--in",
+            &commitments(&participants[0]),
+            "--in",
+            &commitments(&participants[0]),
+        ],
+    );
+    treasury.exec(
+        "prepare",
+        &[
+            "--message",
+            &message,
+            "--participants",
+            &participants.join(","),
+        ],
+    );
+    let unused = ["0", "1", "2"]
+        .into_iter()
+        .find(|participant| !participants.iter().any(|p| p == participant))
+        .unwrap();
+    assert!(Path::new(&commitments(unused)).exists());
+
+    for participant in &participants {
+        treasury.exec("sign", &["--share-index", participant, "--yes"]);
+    }
+    treasury.exec("aggregate", &[]);
+    treasury.exec("verify", &[]);
+
+    let key = serde_json::from_slice::<serde_json::Value>(&treasury.pipe(
+        "info",
+        &["--json", "public-key"],
+        &[],
+    ))
+    .unwrap();
+    assert_eq!(key["threshold"], 2);
+    assert_eq!(key["verifyingShares"].as_array().unwrap().len(), 3);
+
+    // Groups holding key shares are only removed when forced.
+    safe_frost.fail("groups", &["remove", "payroll"]);
+    safe_frost.exec("groups", &["remove", "payroll", "--force"]);
+    safe_frost.fail("groups", &["show", "payroll"]);
+}
+
+/// Nonces are never used twice, even when restored from a backup.
+#[test]
+fn nonce_reuse() {
+    let safe_frost = SafeFrost::with_root_directory("nonce-reuse");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    for participant in ["0", "1"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--message", &random_message()],
+    );
+
+    let nonces = format!("{}/round1.0.nonces", safe_frost.root);
+    let backup = std::fs::read(&nonces).unwrap();
+    safe_frost.exec("sign", &["--share-index", "0", "--yes"]);
+
+    std::fs::write(&nonces, backup).unwrap();
+    safe_frost.fail("sign", &["--share-index", "0", "--yes"]);
+
+    // Shredded nonces are overwritten before they are removed, which is
+    // observable through a second link to the same file.
+    let nonces = format!("{}/round1.1.nonces", safe_frost.root);
+    let link = format!("{}/round1.1.nonces.link", safe_frost.root);
+    std::fs::hard_link(&nonces, &link).unwrap();
+    let len = std::fs::metadata(&link).unwrap().len();
+    safe_frost.exec("sign", &["--share-index", "1", "--yes", "--shred"]);
+    assert!(!Path::new(&nonces).exists());
+    let shredded = std::fs::read(&link).unwrap();
+    assert_eq!(shredded.len() as u64, len);
+    assert!(shredded.iter().all(|&byte| byte == 0));
+}
+
+/// Attach a Safe transaction to a signing session, so that participants can
+/// inspect and approve what they are signing.
+#[test]
+fn transaction() {
+    let safe_frost = SafeFrost::with_root_directory("transaction");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let transaction = format!("{}/transaction.json", safe_frost.root);
+    std::fs::write(
+        &transaction,
+        r#"{
+            "type": "safe",
+            "chainId": 1,
+            "safe": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "1000000000000000000",
+            "data": "0x",
+            "operation": 0,
+            "nonce": 42
+        }"#,
+    )
+    .unwrap();
+
+    for participant in ["0", "1"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.fail(
+        "prepare",
+        &[
+            "--threshold",
+            "2",
+            "--transaction",
+            &transaction,
+            "--message",
+            &random_message(),
+        ],
+    );
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--transaction", &transaction],
+    );
+    safe_frost.exec("info", &["signing-package"]);
+
+    // Signing requires approval, either interactively, or by explicitly
+    // expecting a message or approving.
+    safe_frost.fail("sign", &["--share-index", "0"]);
+    safe_frost.fail(
+        "sign",
+        &["--share-index", "0", "--expect-message", &random_message()],
+    );
+    for participant in ["0", "1"] {
+        safe_frost.exec("sign", &["--share-index", participant, "--yes"]);
+    }
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+
+    // Decode the `execTransaction` calldata for the transaction, with the
+    // FROST signature as a contract signature of a `SafeFROSTSigner` owner.
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let public_key = stdout("info", &["--abi-encode", "public-key"]);
+    let signature = stdout("info", &["--abi-encode", "signature"]);
+    let word = |value: &str| format!("{value:0>64}");
+    let calldata = [
+        "6a761202".to_owned(),
+        word("2222222222222222222222222222222222222222"),
+        word("de0b6b3a7640000"),
+        word("140"),
+        word("0"),
+        word("0"),
+        word("0"),
+        word("0"),
+        word("0"),
+        word("0"),
+        word("160"),
+        word("0"),
+        word("c1"),
+        word("3333333333333333333333333333333333333333"),
+        word("41"),
+        "00".to_owned(),
+        word("60"),
+        format!("{signature:0<192}"),
+        "00".repeat(31),
+    ]
+    .concat();
+    let exec_transaction = format!("{}/exec-transaction", safe_frost.root);
+    std::fs::write(&exec_transaction, calldata).unwrap();
+
+    let options = |nonce| {
+        vec![
+            "exec-transaction".to_owned(),
+            "--in".to_owned(),
+            exec_transaction.clone(),
+            "--chain-id".to_owned(),
+            "1".to_owned(),
+            "--safe".to_owned(),
+            "0x1111111111111111111111111111111111111111".to_owned(),
+            "--nonce".to_owned(),
+            nonce,
+            "--public-key".to_owned(),
+            public_key.clone(),
+            "--signer".to_owned(),
+            "0x3333333333333333333333333333333333333333".to_owned(),
+        ]
+    };
+    let correct = options("42".to_owned());
+    let wrong = options("43".to_owned());
+    safe_frost.exec(
+        "info",
+        &correct.iter().map(String::as_str).collect::<Vec<_>>(),
+    );
+    safe_frost.fail(
+        "info",
+        &wrong.iter().map(String::as_str).collect::<Vec<_>>(),
+    );
+
+    // Tooling can consume the same information as JSON.
+    let json = |options: &[&str]| {
+        serde_json::from_slice::<serde_json::Value>(&safe_frost.pipe("info", options, &[])).unwrap()
+    };
+    let key = json(&["--json", "public-key"]);
+    let exec = json(
+        &[
+            &["--json"][..],
+            &correct.iter().map(String::as_str).collect::<Vec<_>>(),
+        ]
+        .concat(),
+    );
+    assert_eq!(exec["transaction"]["nonce"], "42");
+    assert_eq!(exec["signatures"][0]["type"], "frost");
+    assert_eq!(exec["signatures"][0]["verification"], "valid");
+
+    // Contract signatures of owners other than the FROST signer are opaque.
+    let opaque = json(
+        &[
+            &["--json"][..],
+            &correct[..correct.len() - 2]
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+        ]
+        .concat(),
+    );
+    assert_eq!(opaque["signatures"][0]["type"], "contract");
+    let signing = json(&["--json", "signing-package"]);
+    assert_eq!(signing["message"], exec["safeTxHash"]);
+    let signature = json(&["--json", "signature", "--with-public-key"]);
+    assert_eq!(signature["publicKey"]["address"], key["address"]);
+    safe_frost.fail("info", &["--json", "--abi-encode", "public-key"]);
+}
+
+/// Automatically approve or refuse signing with a signing policy.
+#[test]
+fn policy() {
+    let safe_frost = SafeFrost::with_root_directory("policy");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let write = |name: &str, contents: &str| {
+        let path = format!("{}/{name}", safe_frost.root);
+        std::fs::write(&path, contents).unwrap();
+        path
+    };
+    let transaction = write(
+        "transaction.json",
+        r#"{
+            "type": "safe",
+            "chainId": 100,
+            "safe": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "500",
+            "operation": 1,
+            "nonce": 7
+        }"#,
+    );
+    let allowing = write(
+        "allowing.json",
+        r#"{
+            "chainIds": [100],
+            "safes": ["0x1111111111111111111111111111111111111111"],
+            "to": ["0x2222222222222222222222222222222222222222"],
+            "maxValue": "1000",
+            "allowDelegatecall": true
+        }"#,
+    );
+    let refusing = write(
+        "refusing.json",
+        r#"{
+            "chainIds": [1],
+            "maxValue": 100
+        }"#,
+    );
+
+    for participant in ["0", "1"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--transaction", &transaction],
+    );
+
+    safe_frost.fail("sign", &["--share-index", "0", "--policy", &refusing]);
+    for participant in ["0", "1"] {
+        safe_frost.exec(
+            "sign",
+            &["--share-index", participant, "--policy", &allowing],
+        );
+    }
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+}
+
+/// Verify secret shares from an external dealer before using them.
+#[test]
+fn dealer() {
+    let safe_frost = SafeFrost::with_root_directory("dealer");
+
+    let mut rng = rand::thread_rng();
+    let (shares, pubkey) =
+        frost::keys::generate_with_dealer(3, 2, frost::keys::IdentifierList::Default, &mut rng)
+            .unwrap();
+    let path = |file: &str| format!("{}/{file}", safe_frost.root);
+    std::fs::write(path("key.pub"), pubkey.serialize().unwrap()).unwrap();
+    for (index, share) in shares.values().enumerate() {
+        std::fs::write(path(&format!("share.{index}")), share.serialize().unwrap()).unwrap();
+    }
+
+    safe_frost.exec(
+        "verify",
+        &[
+            "key",
+            "--share-index",
+            "0",
+            "--secret-share",
+            &path("share.0"),
+        ],
+    );
+    safe_frost.fail(
+        "verify",
+        &[
+            "key",
+            "--share-index",
+            "0",
+            "--secret-share",
+            &path("share.1"),
+        ],
+    );
+}
+
+/// Sign with a child key derived from the group key.
+#[test]
+fn derive() {
+    let safe_frost = SafeFrost::with_root_directory("derive");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let group_key = stdout("info", &["public-key"]);
+    let child_key = stdout("info", &["public-key", "--path", "m/0/1"]);
+    assert_ne!(group_key, child_key);
+    assert_eq!(
+        child_key,
+        stdout("info", &["public-key", "--path", "m/0/1"])
+    );
+    safe_frost.fail("info", &["public-key", "--path", "m/2147483648"]);
+    safe_frost.fail("info", &["public-key", "--path", "m/0'/1"]);
+    safe_frost.fail("info", &["public-key", "--path", "m/0/1h"]);
+
+    let message = random_message();
+    for participant in ["0", "2"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec("prepare", &["--threshold", "2", "--message", &message]);
+    for participant in ["0", "2"] {
+        safe_frost.exec(
+            "sign",
+            &[
+                "--share-index",
+                participant,
+                "--expect-message",
+                &message,
+                "--path",
+                "m/0/1",
+            ],
+        );
+    }
+    safe_frost.exec(
+        "verify",
+        &["share", "--share-index", "0", "--path", "m/0/1"],
+    );
+    safe_frost.fail("verify", &["share", "--share-index", "0"]);
+    safe_frost.exec("aggregate", &["--path", "m/0/1"]);
+
+    safe_frost.exec("verify", &["--path", "m/0/1"]);
+    safe_frost.fail("verify", &[]);
+    safe_frost.fail("verify", &["--path", "m/0/2"]);
+
+    let public_key = stdout("info", &["--abi-encode", "public-key", "--path", "m/0/1"]);
+    let signature = stdout("info", &["--abi-encode", "signature"]);
+    safe_frost.exec(
+        "verify",
+        &[
+            "--signature",
+            &signature,
+            "--message",
+            &message,
+            "--public-key",
+            &public_key,
+        ],
+    );
+}
+
+/// Sign for a randomized key that cannot be linked to the group key.
+#[test]
+fn rerandomized() {
+    let safe_frost = SafeFrost::with_root_directory("rerandomized");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let message = random_message();
+    for participant in ["0", "2"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.fail(
+        "prepare",
+        &["--threshold", "2", "--message", &message, "--path", "m/0"],
+    );
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--message", &message, "--rerandomize"],
+    );
+    let signing =
+        serde_json::from_str::<serde_json::Value>(&stdout("info", &["--json", "signing-package"]))
+            .unwrap();
+    assert_eq!(signing["rerandomized"], true);
+
+    for participant in ["0", "2"] {
+        safe_frost.exec(
+            "sign",
+            &["--share-index", participant, "--expect-message", &message],
+        );
+    }
+    safe_frost.exec("verify", &["share", "--share-index", "2"]);
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+    let status = serde_json::from_str::<serde_json::Value>(&stdout("status", &["--json"])).unwrap();
+    assert_eq!(status["signature"], "valid");
+
+    // The signature is for the randomized key, not the group key.
+    let group_key = stdout("info", &["--abi-encode", "public-key"]);
+    let randomized_key = stdout("info", &["--abi-encode", "public-key", "--rerandomized"]);
+    assert_ne!(group_key, randomized_key);
+    let signature = stdout("info", &["--abi-encode", "signature"]);
+    let verify = |public_key: &str| {
+        vec![
+            "--signature".to_owned(),
+            signature.clone(),
+            "--message".to_owned(),
+            message.clone(),
+            "--public-key".to_owned(),
+            public_key.to_owned(),
+        ]
+    };
+    safe_frost.exec(
+        "verify",
+        &verify(&randomized_key)
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+    );
+    safe_frost.fail(
+        "verify",
+        &verify(&group_key)
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+    );
+    let with_public_key = stdout(
+        "info",
+        &[
+            "--abi-encode",
+            "signature",
+            "--with-public-key",
+            "--rerandomized",
+        ],
+    );
+    assert!(with_public_key.starts_with(&randomized_key[64..]));
+
+    // The randomized key can also be displayed for a signing package that is
+    // not in the root directory, such as one from an earlier session.
+    let package = std::fs::read(format!("{}/round1", safe_frost.root)).unwrap();
+    for participant in ["0", "2"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--message", &message, "--rerandomize"],
+    );
+    assert_ne!(
+        stdout("info", &["--abi-encode", "public-key", "--rerandomized"]),
+        randomized_key,
+    );
+    let options = [
+        "--abi-encode",
+        "public-key",
+        "--rerandomized",
+        "--signing-package",
+        "-",
+    ];
+    assert_eq!(
+        String::from_utf8(safe_frost.pipe("info", &options, &package)).unwrap(),
+        randomized_key,
+    );
+    safe_frost.fail("info", &["public-key", "--signing-package", "-"]);
+}
+
+/// Sign directly with the root secret key, for tests that only need a valid
+/// signature.
+#[test]
+fn root_key() {
+    let safe_frost = SafeFrost::with_root_directory("root-key");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+    let root_key = format!("{}/root-key", safe_frost.root);
+    safe_frost.exec("reconstruct", &["--out", &root_key]);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        let mode = std::fs::metadata(&root_key).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let message = random_message();
+    for participant in ["0", "1"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec("prepare", &["--threshold", "2", "--message", &message]);
+
+    safe_frost.fail("sign", &["--root-key", &root_key]);
+    safe_frost.fail(
+        "sign",
+        &[
+            "--root-key",
+            &root_key,
+            "--message",
+            &message,
+            "--share-index",
+            "0",
+        ],
+    );
+    for option in ["--yes", "--shred"] {
+        safe_frost.fail(
+            "sign",
+            &["--root-key", &root_key, "--message", &message, option],
+        );
+    }
+    safe_frost.exec("sign", &["--root-key", &root_key, "--message", &message]);
+    safe_frost.exec("verify", &[]);
+
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let public_key = stdout("info", &["--abi-encode", "public-key"]);
+    let signature = stdout("info", &["--abi-encode", "signature"]);
+    safe_frost.exec(
+        "verify",
+        &[
+            "--signature",
+            &signature,
+            "--message",
+            &message,
+            "--public-key",
+            &public_key,
+        ],
+    );
+}
+
+/// Search for a root key with a vanity address before splitting it.
+#[test]
+fn vanity() {
+    let safe_frost = SafeFrost::with_root_directory("vanity");
+    safe_frost.exec(
+        "split",
+        &[
+            "--threshold",
+            "2",
+            "--signers",
+            "3",
+            "--force",
+            "--address-prefix",
+            "0xA",
+            "--address-suffix",
+            "b",
+        ],
+    );
+    safe_frost.fail(
+        "split",
+        &[
+            "--force",
+            "--address-prefix",
+            "0xa",
+            "--secret-key",
+            &random_message(),
+        ],
+    );
+    for pattern in ["", "0x", &"a".repeat(41)] {
+        safe_frost.fail("split", &["--force", "--address-prefix", pattern]);
+    }
+
+    let info = String::from_utf8(safe_frost.pipe("info", &["public-key"], &[])).unwrap();
+    let address = info
+        .lines()
+        .find_map(|line| line.strip_prefix("address:"))
+        .unwrap()
+        .trim()
+        .to_ascii_lowercase();
+    assert!(address.starts_with("0xa") && address.ends_with('b'));
+}
+
+/// Split secret keys from files and keystores, and reconstruct them again.
+#[test]
+fn keystore() {
+    let safe_frost = SafeFrost::with_root_directory("keystore");
+    let write = |name: &str, contents: &str| {
+        let path = format!("{}/{name}", safe_frost.root);
+        std::fs::write(&path, contents).unwrap();
+        path
+    };
+    let stdout = |subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let split = |options: &[&str]| {
+        let options = [&["--threshold", "2", "--signers", "3", "--force"], options].concat();
+        safe_frost.exec("split", &options);
+    };
+
+    let secret_key = format!("0x{}", random_message());
+    let secret_key_file = write("secret-key", &format!("{secret_key}\n"));
+    let password_file = write("password", "correct horse battery staple\n");
+    let wrong_password_file = write("wrong-password", "hunter2\n");
+
+    split(&["--secret-key-file", &secret_key_file]);
+    let public_key = stdout("info", &["public-key"]);
+    assert_eq!(stdout("reconstruct", &["--out", "-"]).trim(), secret_key);
+
+    let key_share = format!("{}/key.0", safe_frost.root);
+    safe_frost.fail("reconstruct", &["--out", "-", "--in", &key_share]);
+
+    let keystore = format!("{}/keystore.json", safe_frost.root);
+    safe_frost.exec(
+        "reconstruct",
+        &[
+            "--out",
+            &keystore,
+            "--keystore",
+            "--password-file",
+            &password_file,
+        ],
+    );
+    safe_frost.fail(
+        "split",
+        &[
+            "--force",
+            "--keystore",
+            &keystore,
+            "--password-file",
+            &wrong_password_file,
+        ],
+    );
+    split(&["--keystore", &keystore, "--password-file", &password_file]);
+    assert_eq!(stdout("info", &["public-key"]), public_key);
+
+    // PBKDF2 test vector from the Web3 Secret Storage definition.
+    let keystore = write(
+        "test-vector.json",
+        r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#,
+    );
+    let password_file = write("test-vector-password", "testpassword");
+    split(&["--keystore", &keystore, "--password-file", &password_file]);
+    assert_eq!(
+        stdout("reconstruct", &["--out", "-"]).trim(),
+        "0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d",
+    );
+
+    // Key derivation parameters that are too expensive are refused, instead
+    // of hanging or exhausting memory.
+    let vector = std::fs::read_to_string(&keystore).unwrap();
+    let expensive = [
+        vector.replace("\"c\": 262144", "\"c\": 4294967295"),
+        vector.replace("\"dklen\": 32", "\"dklen\": 4294967295"),
+        vector.replace(
+            r#""kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256","#,
+            r#""kdf": "scrypt",
+                "kdfparams": {
+                    "n": 1099511627776,
+                    "r": 8,
+                    "p": 1,
+                    "dklen": 32,"#,
+        ),
+    ];
+    for (i, contents) in expensive.iter().enumerate() {
+        assert_ne!(contents, &vector);
+        let keystore = write(&format!("expensive-{i}.json"), contents);
+        safe_frost.fail(
+            "split",
+            &[
+                "--force",
+                "--keystore",
+                &keystore,
+                "--password-file",
+                &password_file,
+            ],
+        );
+    }
+}
+
+/// Split the secret key of a BIP-39 mnemonic account.
+#[test]
+fn mnemonic() {
+    let safe_frost = SafeFrost::with_root_directory("mnemonic");
+    let mnemonic = format!("{}/mnemonic", safe_frost.root);
+    std::fs::write(
+        &mnemonic,
+        "test test test test test test test test test test test junk\n",
+    )
+    .unwrap();
+    let split = |options: &[&str], success: bool| {
+        let options = [
+            &["--threshold", "2", "--signers", "3", "--force"],
+            &["--mnemonic-file", &mnemonic][..],
+            options,
+        ]
+        .concat();
+        if success {
+            safe_frost.exec("split", &options);
+        } else {
+            safe_frost.fail("split", &options);
+        }
+    };
+
+    split(&[], false);
+    split(
+        &[
+            "--expect-address",
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+        ],
+        false,
+    );
+    split(
+        &[
+            "--expect-address",
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+        ],
+        true,
+    );
+    let secret_key = safe_frost.pipe("reconstruct", &["--out", "-"], &[]);
+    assert_eq!(
+        String::from_utf8(secret_key).unwrap().trim(),
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    );
+
+    split(&["--derivation-path", "m/44'/60'/0'/0/1", "--yes"], true);
+    let info = safe_frost.pipe("info", &["public-key"], &[]);
+    assert!(
+        String::from_utf8(info)
+            .unwrap()
+            .contains("0x70997970C51812dc3A010C7d01b50e0d17dc79C8")
+    );
+}
+
+/// Back up a key share on paper, and restore it again.
+#[test]
+fn paper() {
+    let safe_frost = SafeFrost::with_root_directory("paper");
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3", "--force"]);
+
+    let key_share = format!("{}/key.1", safe_frost.root);
+    let backup = format!("{}/backup", safe_frost.root);
+    let corrupted = format!("{}/corrupted", safe_frost.root);
+    safe_frost.exec(
+        "export",
+        &["share", "--share-index", "1", "--paper", "--out", &backup],
+    );
+
+    // Transcription errors are detected, and the key share is left untouched.
+    let paper = std::fs::read_to_string(&backup).unwrap();
+    let line = paper.lines().find(|line| line.starts_with(" 3 ")).unwrap();
+    let digit = if &line[4..5] == "0" { "1" } else { "0" };
+    let typo = format!("{}{digit}{}", &line[..4], &line[5..]);
+    std::fs::write(&corrupted, paper.replace(line, &typo)).unwrap();
+    safe_frost.fail("import", &["share", "--in", &corrupted, "--force"]);
+    std::fs::write(&corrupted, paper.replace(line, "")).unwrap();
+    safe_frost.fail("import", &["share", "--in", &corrupted, "--force"]);
+
+    let original = std::fs::read(&key_share).unwrap();
+    std::fs::remove_file(&key_share).unwrap();
+    safe_frost.fail("import", &["share", "--in", &backup, "--share-index", "2"]);
+    safe_frost.exec("import", &["share", "--in", &backup, "--share-index", "1"]);
+    assert_eq!(std::fs::read(&key_share).unwrap(), original);
+    safe_frost.fail("import", &["share", "--in", &backup]);
+
+    // Exported key shares do not record their share index, so it must be
+    // given explicitly.
+    let exported = format!("{}/exported", safe_frost.root);
+    safe_frost.exec(
+        "export",
+        &["share", "--share-index", "1", "--out", &exported],
+    );
+    safe_frost.fail("import", &["share", "--in", &exported, "--force"]);
+    safe_frost.fail(
+        "import",
+        &["share", "--in", &exported, "--share-index", "2", "--force"],
+    );
+    safe_frost.exec(
+        "import",
+        &["share", "--in", &exported, "--share-index", "1", "--force"],
+    );
+    assert_eq!(std::fs::read(&key_share).unwrap(), original);
+}
+
+/// Record signing activity in a hash-chained audit log.
+#[test]
+fn audit() {
+    let safe_frost = SafeFrost::with_root_directory("audit");
+
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3"]);
+    for participant in ["0", "2"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    let message = random_message();
+    safe_frost.exec("prepare", &["--threshold", "2", "--message", &message]);
+    for participant in ["0", "2"] {
+        safe_frost.exec("sign", &["--share-index", participant, "--yes"]);
+    }
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+
+    let log = format!("{}/audit.log", safe_frost.root);
+    let entries = std::fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    let events = entries
+        .iter()
+        .map(|entry| entry["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            "split",
+            "commit",
+            "commit",
+            "prepare",
+            "sign",
+            "sign",
+            "aggregate",
+            "verify"
+        ],
+    );
+    assert_eq!(entries[3]["message"], format!("0x{message}"));
+    assert_eq!(entries[3]["participants"], serde_json::json!(["1", "3"]));
+    assert_eq!(entries[5]["participant"], "3");
+    assert_eq!(entries[5]["commitments"], entries[2]["commitments"]);
+    assert_eq!(entries[7]["valid"], true);
+
+    let output = String::from_utf8(safe_frost.pipe("audit", &["verify"], &[])).unwrap();
+    let head = output.trim().rsplit(' ').next().unwrap().to_owned();
+    safe_frost.exec("audit", &["verify", "--head", &head]);
+
+    // Verifying with explicit inputs does not create a root directory just
+    // for its audit log.
+    let elsewhere = SafeFrost::with_root_directory("audit-elsewhere");
+    std::fs::remove_dir_all(&elsewhere.root).unwrap();
+    let file = |name: &str| format!("{}/{name}", safe_frost.root);
+    elsewhere.exec(
+        "verify",
+        &[
+            "--in",
+            &file("round2"),
+            "--signing-package",
+            &file("round1"),
+            "--public-key-package",
+            &file("key.pub"),
+        ],
+    );
+    assert!(!Path::new(&elsewhere.root).exists());
+
+    // Rewriting history breaks the chain, and truncating it loses the head.
+    let original = std::fs::read_to_string(&log).unwrap();
+    let lines = original.lines().collect::<Vec<_>>();
+    std::fs::write(
+        &log,
+        original.replace(lines[5], &lines[5].replace("\"3\"", "\"2\"")),
+    )
+    .unwrap();
+    safe_frost.fail("audit", &["verify"]);
+    std::fs::write(&log, format!("{}\n", lines[..7].join("\n"))).unwrap();
+    safe_frost.exec("audit", &["verify"]);
+    safe_frost.fail("audit", &["verify", "--head", &head]);
+    std::fs::write(&log, original).unwrap();
+}
+
+/// Default options from a `safe-frost.toml` configuration file.
+#[test]
+fn config() {
+    let safe_frost = SafeFrost::with_root_directory("config");
+
+    let write = |name: &str, contents: &str| {
+        let path = format!("{}/{name}", safe_frost.root);
+        std::fs::write(&path, contents).unwrap();
+        path
+    };
+    let config = write(
+        "safe-frost.toml",
+        r#"
+            format = "json"
+            chain-id = 100
+            safe = "0x1111111111111111111111111111111111111111"
+            policy = "policy.json"
+        "#,
+    );
+    write(
+        "policy.json",
+        r#"{
+            "chainIds": [100],
+            "safes": ["0x1111111111111111111111111111111111111111"],
+            "maxValue": "1000"
+        }"#,
+    );
+    // The chain ID and Safe are filled in from the configuration.
+    let transaction = write(
+        "transaction.json",
+        r#"{
+            "type": "safe",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "500",
+            "nonce": 7
+        }"#,
+    );
+
+    let stdout = |safe_frost: &SafeFrost, subcommand: &str, options: &[&str]| {
+        String::from_utf8(safe_frost.pipe(subcommand, options, &[])).unwrap()
+    };
+    let show = stdout(&safe_frost, "config", &["show"]);
+    let show = serde_json::from_str::<serde_json::Value>(&show).unwrap();
+    assert_eq!(show["format"], "json");
+    assert_eq!(show["chain-id"], "100");
+    assert_eq!(show["layout"], "flat");
+    let text = safe_frost.with_option("--format", "text");
+    let show = stdout(&text, "config", &["show"]);
+    assert!(show.contains(&format!("# loaded from {config}")));
+    assert!(show.contains("format = \"text\""));
+
+    safe_frost.exec("split", &["--threshold", "2", "--signers", "3"]);
+    for participant in ["0", "1"] {
+        safe_frost.exec("commit", &["--share-index", participant]);
+    }
+    safe_frost.exec(
+        "prepare",
+        &["--threshold", "2", "--transaction", &transaction],
+    );
+
+    // Output is JSON by default, and signing is approved by the policy.
+    let status = stdout(&safe_frost, "status", &[]);
+    let status = serde_json::from_str::<serde_json::Value>(&status).unwrap();
+    assert_eq!(status["stage"], "sign");
+    let signing = stdout(&safe_frost, "info", &["signing-package"]);
+    let signing = serde_json::from_str::<serde_json::Value>(&signing).unwrap();
+    assert_eq!(signing["transaction"]["chainId"], "100");
+    for participant in ["0", "1"] {
+        safe_frost.exec("sign", &["--share-index", participant]);
+    }
+    safe_frost.exec("aggregate", &[]);
+    safe_frost.exec("verify", &[]);
+
+    // Unknown options are rejected rather than silently ignored.
+    std::fs::write(&config, "chain = 1\n").unwrap();
+    safe_frost.fail("config", &["show"]);
+    std::fs::remove_file(&config).unwrap();
 }
 
 struct SafeFrost {
     root: String,
+    options: Vec<String>,
 }
 
 impl SafeFrost {
+    /// Uses a fresh root directory for a test, outside of the working tree so
+    /// that test runs never leave key material behind in the repository.
     fn with_root_directory(tag: &str) -> Self {
-        let root = Path::new(".frost")
-            .join(tag)
-            .into_os_string()
-            .into_string()
-            .unwrap();
-        Self { root }
+        let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(tag);
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        Self {
+            root: root.into_os_string().into_string().unwrap(),
+            options: Vec::new(),
+        }
+    }
+
+    fn layout(&self, layout: &str) -> Self {
+        self.with_option("--layout", layout)
+    }
+
+    fn group(&self, group: &str) -> Self {
+        self.with_option("--group", group)
+    }
+
+    fn with_option(&self, option: &str, value: &str) -> Self {
+        let mut options = self.options.clone();
+        options.extend([option.to_string(), value.to_string()]);
+        Self {
+            root: self.root.clone(),
+            options,
+        }
     }
 
     fn exec(&self, subcommand: &str, options: &[&str]) {
+        let output = self.pipe(subcommand, options, &[]);
+        print!("{}", String::from_utf8_lossy(&output));
+    }
+
+    /// Executes a command with the specified standard input, returning its
+    /// standard output.
+    fn pipe(&self, subcommand: &str, options: &[&str], input: &[u8]) -> Vec<u8> {
+        let output = self.run(subcommand, options, input);
+        assert!(output.status.success(), "`safe-frost` command failed");
+        output.stdout
+    }
+
+    /// Executes a command that is expected to fail.
+    fn fail(&self, subcommand: &str, options: &[&str]) {
+        let output = self.run(subcommand, options, &[]);
+        assert!(!output.status.success(), "`safe-frost` command succeeded");
+    }
+
+    fn run(&self, subcommand: &str, options: &[&str], input: &[u8]) -> Output {
         print!("$ safe-frost {subcommand}");
         for option in options {
             print!(" {option}");
         }
         println!();
-        let output = Command::new("cargo")
+        let mut child = Command::new("cargo")
             .args(["run", "-q", "--"])
             .args(["--root-directory", &self.root])
+            // Keep the user configuration out of the tests.
+            .env("XDG_CONFIG_HOME", Path::new(&self.root).join("xdg"))
+            .args(&self.options)
             .arg(subcommand)
             .args(options)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
             .stderr(Stdio::inherit())
-            .output()
+            .spawn()
             .expect("Failed to execute `safe-frost`");
-        assert!(output.status.success(), "`safe-frost` command failed");
-        print!("{}", String::from_utf8_lossy(&output.stdout));
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
     }
 }
 
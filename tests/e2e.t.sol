@@ -107,7 +107,7 @@ contract E2ETest is Test {
         // Once the threshold of signature shares have been collected, the
         // Coordinator can generate a Schnorr signature.
         for (uint256 i = 0; i < participants.length; i++) {
-            safeFROST.exec("sign", "--share-index", participants[i]);
+            safeFROST.exec("sign", "--share-index", participants[i], "--expect-message", vm.toString(transactionHash));
         }
         safeFROST.exec("aggregate");
 
@@ -172,7 +172,7 @@ contract E2ETest is Test {
 
         // Round 2.
         for (uint256 i = 0; i < participants.length; i++) {
-            safeFROST.exec("sign", "--share-index", participants[i]);
+            safeFROST.exec("sign", "--share-index", participants[i], "--expect-message", vm.toString(transactionHash));
         }
         safeFROST.exec("aggregate");
 
@@ -229,7 +229,7 @@ contract E2ETest is Test {
 
         // Round 2.
         for (uint256 i = 0; i < participants.length; i++) {
-            safeFROST.exec("sign", "--share-index", participants[i]);
+            safeFROST.exec("sign", "--share-index", participants[i], "--expect-message", vm.toString(userOpHash));
         }
         safeFROST.exec("aggregate");
 